@@ -1,11 +1,19 @@
 //! Information about the physical link layer between the vehicle and ground station.
 
+use serde::{Deserialize, Serialize};
+
+pub mod frame;
+
 /// Enum containing all physical interlink methods.
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum InterlinkMethod {
     /// Physical commination takes place over a [`serial`] port.
     Serial,
+    /// Frames are relayed over a [`net`]work in UDP datagrams.
+    Udp,
+    /// Frames are relayed over a [`net`]work in a TCP stream.
+    Tcp,
 }
 
 /// Universal Serial Bus.
@@ -25,10 +33,21 @@ pub mod serial {
     /// The size of the Serial buffer to use.
     pub const BUFFER_SIZE: usize = 2048;
 
-    /// The sentinel byte to delimit packets.
+    /// The sentinel byte to delimit [`frame`](super::frame)s.
     pub const COBS_SENTINEL: u8 = 0x00;
 }
 
+/// Network relay.
+///
+/// A receiver away from the station, such as one at the launch pad, may relay
+/// frames over a network. Frames use the same [`frame`] format as the [`serial`]
+/// link, delimited by [`serial::COBS_SENTINEL`], and may be split across or
+/// batched into UDP datagrams or TCP segments freely.
+pub mod net {
+    /// The port the station listens on unless configured otherwise.
+    pub const DEFAULT_PORT: u16 = 5700;
+}
+
 // TODO: :D
 // pub mod ble {}
 // pub mod rf {}
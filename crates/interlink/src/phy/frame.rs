@@ -0,0 +1,204 @@
+//! Framing of packets sent over any physical link.
+//!
+//! Every packet is serialized with [`postcard`], followed by a little endian
+//! CRC-16/IBM-3740 of the serialized bytes. The whole is then COBS encoded and
+//! terminated by [`serial::COBS_SENTINEL`](super::serial::COBS_SENTINEL), so a
+//! receiver can find the start of the next frame after any corruption and
+//! reject frames that were damaged in transit.
+
+use core::fmt::{self, Display, Formatter};
+
+use crc::{Crc, Digest, CRC_16_IBM_3740};
+use postcard::flavors::{Cobs, SerFlavor, Slice};
+use serde::{Deserialize, Serialize};
+
+/// The CRC algorithm protecting every frame.
+static CRC: Crc<u16> = Crc::<u16>::new(&CRC_16_IBM_3740);
+
+/// Size of the CRC trailer in bytes.
+pub const CRC_SIZE: usize = core::mem::size_of::<u16>();
+
+/// Error encountered while encoding or decoding a frame.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FrameError {
+    /// The value could not be serialized, most likely because the buffer was too small.
+    Serialize(postcard::Error),
+    /// The frame is not valid COBS.
+    Encoding,
+    /// The frame is too short to contain a CRC trailer.
+    Truncated,
+    /// The CRC trailer does not match the contents of the frame.
+    Checksum {
+        /// The CRC sent in the trailer.
+        expected: u16,
+        /// The CRC calculated from the received payload.
+        actual: u16,
+    },
+    /// The payload passed its CRC but could not be deserialized.
+    Deserialize(postcard::Error),
+}
+
+impl Display for FrameError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            FrameError::Serialize(error) => write!(f, "unable to serialize frame: {}", error),
+            FrameError::Encoding => write!(f, "frame is not valid COBS"),
+            FrameError::Truncated => write!(f, "frame is too short to contain a CRC"),
+            FrameError::Checksum { expected, actual } => write!(
+                f,
+                "CRC mismatch, expected {:#06X} but calculated {:#06X}",
+                expected, actual
+            ),
+            FrameError::Deserialize(error) => write!(f, "unable to deserialize frame: {}", error),
+        }
+    }
+}
+
+/// Serialize `value` into a frame in `buffer`, including the trailing sentinel.
+///
+/// # Errors
+///
+/// Returns [`FrameError::Serialize`] if `value` could not be serialized or the
+/// frame does not fit in `buffer`.
+pub fn encode<'b, T>(value: &T, buffer: &'b mut [u8]) -> Result<&'b mut [u8], FrameError>
+where
+    T: Serialize + ?Sized,
+{
+    let flavor = Crc16 {
+        inner: Cobs::try_new(Slice::new(buffer)).map_err(FrameError::Serialize)?,
+        digest: CRC.digest(),
+    };
+
+    postcard::serialize_with_flavor(value, flavor).map_err(FrameError::Serialize)
+}
+
+/// Deserialize a frame, with or without its trailing sentinel.
+///
+/// The frame is decoded in place, so `frame` will contain garbage afterwards.
+///
+/// # Errors
+///
+/// Returns an error if the frame is not valid COBS, fails its CRC check or
+/// does not contain a `T`.
+pub fn decode<'f, T>(frame: &'f mut [u8]) -> Result<T, FrameError>
+where
+    T: Deserialize<'f>,
+{
+    // The decoder would treat the sentinel as part of the frame
+    let frame = match frame
+        .iter()
+        .position(|&byte| byte == super::serial::COBS_SENTINEL)
+    {
+        Some(end) => &mut frame[..end],
+        None => frame,
+    };
+
+    let length = postcard_cobs::decode_in_place(frame).map_err(|()| FrameError::Encoding)?;
+
+    let payload_length = length.checked_sub(CRC_SIZE).ok_or(FrameError::Truncated)?;
+    let (payload, trailer) = frame[..length].split_at(payload_length);
+
+    let expected = u16::from_le_bytes([trailer[0], trailer[1]]);
+    let actual = CRC.checksum(payload);
+
+    if expected != actual {
+        return Err(FrameError::Checksum { expected, actual });
+    }
+
+    postcard::from_bytes(payload).map_err(FrameError::Deserialize)
+}
+
+/// Flavor appending the CRC of everything serialized through it.
+struct Crc16<F> {
+    inner: F,
+    digest: Digest<'static, u16>,
+}
+
+impl<F: SerFlavor> SerFlavor for Crc16<F> {
+    type Output = F::Output;
+
+    fn try_extend(&mut self, data: &[u8]) -> Result<(), ()> {
+        self.digest.update(data);
+        self.inner.try_extend(data)
+    }
+
+    fn try_push(&mut self, data: u8) -> Result<(), ()> {
+        self.digest.update(&[data]);
+        self.inner.try_push(data)
+    }
+
+    fn release(mut self) -> Result<Self::Output, ()> {
+        let crc = self.digest.finalize();
+
+        self.inner.try_extend(&crc.to_le_bytes())?;
+        self.inner.release()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, encode, FrameError, CRC_SIZE};
+
+    type Value = (u32, u16, [u8; 4]);
+
+    const VALUE: Value = (0xDEAD_BEEF, 1234, [1, 2, 3, 4]);
+
+    /// Encode [`VALUE`], returning the buffer and the length of the frame
+    fn frame() -> ([u8; 32], usize) {
+        let mut buffer = [0; 32];
+        let length = encode(&VALUE, &mut buffer)
+            .expect("value should fit in the buffer")
+            .len();
+
+        (buffer, length)
+    }
+
+    #[test]
+    fn round_trip() {
+        let (mut buffer, length) = frame();
+
+        assert_eq!(decode::<Value>(&mut buffer[..length]), Ok(VALUE));
+    }
+
+    #[test]
+    fn flipped_payload_bit() {
+        let (mut buffer, length) = frame();
+
+        // The first byte is COBS overhead, the one after it is payload
+        buffer[1] ^= 0b0000_0010;
+
+        assert!(matches!(
+            decode::<Value>(&mut buffer[..length]),
+            Err(FrameError::Checksum { .. })
+        ));
+    }
+
+    #[test]
+    fn flipped_crc_byte() {
+        let (mut buffer, length) = frame();
+
+        // The CRC trailer comes right before the sentinel
+        buffer[length - 1 - CRC_SIZE] ^= 0xFF;
+
+        assert!(matches!(
+            decode::<Value>(&mut buffer[..length]),
+            Err(FrameError::Checksum { .. })
+        ));
+    }
+
+    #[test]
+    fn truncated_frame() {
+        let (mut buffer, length) = frame();
+
+        // Lose the last byte of the CRC, shortening the frame's only COBS
+        // block so that the frame still decodes and only the CRC can tell
+        buffer[0] -= 1;
+        buffer[length - 2] = buffer[length - 1];
+
+        assert!(matches!(
+            decode::<Value>(&mut buffer[..length - 1]),
+            Err(FrameError::Checksum { .. })
+        ));
+    }
+}
@@ -1,34 +1,355 @@
 //! Structures and types used to represent the communications protocol between the station and a vehicle.
 
+use core::{
+    fmt::{self, Display, Formatter},
+    ops::BitOr,
+};
+
 use serde::{Deserialize, Serialize};
 
 use crate::vehicle_time::VehicleTime;
 
+/// Version of the protocol defined in this module.
+///
+/// Must be incremented whenever the serialized layout of any packet changes.
+pub const PROTOCOL_VERSION: u16 = 1;
+
+/// Protocol spoken by one end of the link, exchanged when connecting.
+///
+/// [`PacketUp::Welcome`] and [`PacketDownData::Hello`] must stay the first
+/// variants of their packets, with this as their first field, so that both
+/// ends can read each other's protocol whatever its version.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Protocol {
+    /// The [`PROTOCOL_VERSION`] this end was built with.
+    pub version: u16,
+    /// Optional parts of the protocol this end supports.
+    pub capabilities: Capabilities,
+}
+
+impl Protocol {
+    /// The protocol defined in this module, supporting the given capabilities.
+    pub const fn current(capabilities: Capabilities) -> Self {
+        Self {
+            version: PROTOCOL_VERSION,
+            capabilities,
+        }
+    }
+}
+
+/// Set of optional parts of the protocol.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Capabilities(u32);
+
+impl Capabilities {
+    /// No optional parts of the protocol.
+    pub const NONE: Capabilities = Capabilities(0);
+    /// Carrying out [`PacketUp::Command`]s.
+    pub const COMMANDS: Capabilities = Capabilities(1 << 0);
+    /// Sending [`PacketDownData::Gnss`] fixes.
+    pub const GNSS: Capabilities = Capabilities(1 << 1);
+    /// Sending [`PacketDownData::Barometer`] readings.
+    pub const BAROMETER: Capabilities = Capabilities(1 << 2);
+    /// Sending [`PacketDownData::Gyroscope`] readings.
+    pub const GYROSCOPE: Capabilities = Capabilities(1 << 3);
+    /// Sending [`PacketDownData::Power`] readings.
+    pub const POWER: Capabilities = Capabilities(1 << 4);
+    /// Reporting the phase of flight with [`PacketDownData::FlightPhase`].
+    pub const FLIGHT_PHASE: Capabilities = Capabilities(1 << 5);
+    /// Reporting pyrotechnic channels with [`PacketDownData::Pyro`] and
+    /// firing them with [`Command::FirePyro`].
+    pub const PYRO: Capabilities = Capabilities(1 << 6);
+    /// Answering [`PacketUp::TimeSync`] requests with a [`PacketDownData::TimeSync`].
+    pub const TIME_SYNC: Capabilities = Capabilities(1 << 7);
+    /// Every capability known to this version of the protocol.
+    pub const ALL: Capabilities = Capabilities(
+        Capabilities::COMMANDS.0
+            | Capabilities::GNSS.0
+            | Capabilities::BAROMETER.0
+            | Capabilities::GYROSCOPE.0
+            | Capabilities::POWER.0
+            | Capabilities::FLIGHT_PHASE.0
+            | Capabilities::PYRO.0
+            | Capabilities::TIME_SYNC.0,
+    );
+
+    /// Whether every capability in `other` is also in `self`.
+    pub const fn contains(self, other: Capabilities) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Capabilities in `self` that are missing from `other`.
+    pub const fn difference(self, other: Capabilities) -> Capabilities {
+        Capabilities(self.0 & !other.0)
+    }
+
+    /// Whether there are no capabilities in the set.
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl BitOr for Capabilities {
+    type Output = Capabilities;
+
+    fn bitor(self, rhs: Capabilities) -> Capabilities {
+        Capabilities(self.0 | rhs.0)
+    }
+}
+
+impl Display for Capabilities {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let names = [
+            (Capabilities::COMMANDS, "commands"),
+            (Capabilities::GNSS, "GNSS"),
+            (Capabilities::BAROMETER, "barometer"),
+            (Capabilities::GYROSCOPE, "gyroscope"),
+            (Capabilities::POWER, "power"),
+            (Capabilities::FLIGHT_PHASE, "flight phase"),
+            (Capabilities::PYRO, "pyro"),
+            (Capabilities::TIME_SYNC, "time sync"),
+        ];
+
+        let mut first = true;
+
+        for (capability, name) in names {
+            if self.contains(capability) {
+                if !first {
+                    write!(f, ", ")?;
+                }
+
+                write!(f, "{}", name)?;
+                first = false;
+            }
+        }
+
+        if first {
+            write!(f, "none")?;
+        }
+
+        Ok(())
+    }
+}
+
 /// Packet sent from the station to the vehicle.
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub enum PacketUp {
-    /// Packet sent to the vehicle upon connecting.
+    /// Packet sent to the vehicle upon connecting, carrying the station's protocol.
     ///
     /// The vehicle should respond with a [`PacketDownData::Hello`].
-    Welcome,
+    Welcome(Protocol),
+    /// A command for the vehicle to carry out.
+    ///
+    /// The vehicle must answer every command with either a [`PacketDownData::Ack`],
+    /// a [`PacketDownData::Pong`] for a [`Command::Ping`], or a [`PacketDownData::Nak`]
+    /// carrying the same `id`.
+    Command {
+        /// Identifies the command in the vehicle's response.
+        id: CommandId,
+        /// What the vehicle should do.
+        command: Command,
+    },
+    /// Request for the vehicle's clock, used to measure the round trip time
+    /// of the link and the offset between the station's clock and the vehicle's.
+    ///
+    /// The vehicle should respond with a [`PacketDownData::TimeSync`] as soon as it can.
+    TimeSync {
+        /// The station's clock when the request was sent, in microseconds since
+        /// the Unix epoch, to be echoed back unchanged.
+        station_time: u64,
+    },
+}
+
+/// Identifier chosen by the station to match responses to commands.
+pub type CommandId = u16;
+
+/// Commands the station can send to a vehicle.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    /// Allow the vehicle to perform irreversible actions such as launching.
+    Arm,
+    /// Prevent the vehicle from performing irreversible actions.
+    Disarm,
+    /// Check that the vehicle is responsive.
+    ///
+    /// The vehicle responds with a [`PacketDownData::Pong`] echoing the nonce.
+    Ping {
+        /// Arbitrary value to be echoed back by the vehicle.
+        nonce: u32,
+    },
+    /// Restart the vehicle's firmware.
+    ///
+    /// The vehicle acknowledges the command before restarting.
+    Reboot,
+    /// Ask the vehicle to send a [`PacketDownData::Hello`] without reconnecting.
+    RequestIdentification,
+    /// Change how often a sensor's readings are sent to the station.
+    SetTelemetryRate {
+        /// The sensor to change the rate of.
+        sensor: Sensor,
+        /// Readings to send per second, where 0 stops sending the sensor entirely.
+        rate: u16,
+    },
+    /// Fire one of the vehicle's pyrotechnic channels.
+    ///
+    /// The vehicle refuses with [`NakReason::InvalidState`] unless the channel
+    /// is armed, and with [`NakReason::InvalidArgument`] if it has no such channel.
+    FirePyro {
+        /// Index of the channel in [`PacketDownData::Pyro`].
+        channel: u8,
+    },
+}
+
+/// Sensors that stream telemetry to the station.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Sensor {
+    /// Sent as [`PacketDownData::Magnetometer`].
+    Magnetometer,
+    /// Sent as [`PacketDownData::Accelerometer`].
+    Accelerometer,
+    /// Sent as [`PacketDownData::ECompassTemperature`].
+    ECompassTemperature,
+    /// Sent as [`PacketDownData::Gnss`].
+    Gnss,
+    /// Sent as [`PacketDownData::Barometer`].
+    Barometer,
+    /// Sent as [`PacketDownData::Gyroscope`].
+    Gyroscope,
+    /// Sent as [`PacketDownData::Power`].
+    Power,
+}
+
+impl Sensor {
+    /// All sensors.
+    pub const ALL: &'static [Sensor] = &[
+        Sensor::Magnetometer,
+        Sensor::Accelerometer,
+        Sensor::ECompassTemperature,
+        Sensor::Gnss,
+        Sensor::Barometer,
+        Sensor::Gyroscope,
+        Sensor::Power,
+    ];
+}
+
+impl Display for Sensor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Sensor::Magnetometer => write!(f, "Magnetometer"),
+            Sensor::Accelerometer => write!(f, "Accelerometer"),
+            Sensor::ECompassTemperature => write!(f, "eCompass Temperature"),
+            Sensor::Gnss => write!(f, "GNSS"),
+            Sensor::Barometer => write!(f, "Barometer"),
+            Sensor::Gyroscope => write!(f, "Gyroscope"),
+            Sensor::Power => write!(f, "Power"),
+        }
+    }
+}
+
+/// State of the vehicle's flight state machine.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FlightPhase {
+    /// On the ground and safe.
+    Idle,
+    /// On the ground and ready to launch.
+    Armed,
+    /// Motor burning.
+    Boost,
+    /// Ballistic flight after burnout.
+    Coast,
+    /// Highest point reached, waiting for recovery to deploy.
+    Apogee,
+    /// Falling under recovery.
+    Descent,
+    /// Back on the ground after a flight.
+    Landed,
+    /// Something has gone wrong and the vehicle has stopped its sequence.
+    Fault,
+}
+
+impl FlightPhase {
+    /// All phases, in the order they happen during a flight.
+    pub const ALL: &'static [FlightPhase] = &[
+        FlightPhase::Idle,
+        FlightPhase::Armed,
+        FlightPhase::Boost,
+        FlightPhase::Coast,
+        FlightPhase::Apogee,
+        FlightPhase::Descent,
+        FlightPhase::Landed,
+        FlightPhase::Fault,
+    ];
+}
+
+impl Display for FlightPhase {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            FlightPhase::Idle => write!(f, "Idle"),
+            FlightPhase::Armed => write!(f, "Armed"),
+            FlightPhase::Boost => write!(f, "Boost"),
+            FlightPhase::Coast => write!(f, "Coast"),
+            FlightPhase::Apogee => write!(f, "Apogee"),
+            FlightPhase::Descent => write!(f, "Descent"),
+            FlightPhase::Landed => write!(f, "Landed"),
+            FlightPhase::Fault => write!(f, "Fault"),
+        }
+    }
+}
+
+/// Reason for a vehicle refusing a command.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NakReason {
+    /// The vehicle does not implement the command.
+    Unsupported,
+    /// The command cannot be carried out in the vehicle's current state.
+    InvalidState,
+    /// An argument of the command is out of range.
+    InvalidArgument,
+}
+
+impl Display for NakReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            NakReason::Unsupported => write!(f, "unsupported"),
+            NakReason::InvalidState => write!(f, "invalid state"),
+            NakReason::InvalidArgument => write!(f, "invalid argument"),
+        }
+    }
 }
 
 /// Packet sent from the vehicle to the station.
+///
+/// The fields before `data` must keep their layout across protocol versions,
+/// so that a [`PacketDownData::Hello`] can always be read.
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct PacketDown {
+    /// Position of the packet in the stream of packets sent since the vehicle booted.
+    ///
+    /// Incremented by one for every packet and wrapping around on overflow, so the
+    /// station can detect packets that were lost, duplicated or reordered in transit.
+    pub sequence: SequenceNumber,
     /// When the packet was sent.
     pub time: VehicleTime,
     /// Data contained within the packet.
     pub data: PacketDownData,
 }
 
+/// Counter identifying a [`PacketDown`] in the stream of packets from the vehicle.
+pub type SequenceNumber = u16;
+
 /// Data portion of a packet sent from the vehicle to the station.
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub enum PacketDownData {
-    /// Sent in response to [`PacketUp::Welcome`] to identify the vehicle.
+    /// Sent in response to [`PacketUp::Welcome`] to identify the vehicle and its protocol.
     Hello(VehicleIdentification),
     /// Magnetometer data in nT (nanotesla).
     Magnetometer(Vector3<i32>),
@@ -36,12 +357,144 @@ pub enum PacketDownData {
     Accelerometer(Vector3<i32>),
     /// eCompass temperature data in *C
     ECompassTemperature(f32),
+    /// The command with the given id was carried out.
+    Ack(CommandId),
+    /// The command with the given id was refused.
+    Nak {
+        /// Id of the refused command.
+        id: CommandId,
+        /// Why the command was refused.
+        reason: NakReason,
+    },
+    /// Sent instead of an [`PacketDownData::Ack`] in response to a [`Command::Ping`].
+    Pong {
+        /// Id of the ping command.
+        id: CommandId,
+        /// The nonce sent with the ping.
+        nonce: u32,
+    },
+    /// Position from the GNSS receiver, sent by vehicles with [`Capabilities::GNSS`].
+    Gnss(GnssFix),
+    /// Static pressure from the barometer, sent by vehicles with [`Capabilities::BAROMETER`].
+    Barometer(BarometerReading),
+    /// Angular rate in millidegrees per second about each axis, sent by
+    /// vehicles with [`Capabilities::GYROSCOPE`].
+    Gyroscope(Vector3<i32>),
+    /// State of the battery and power bus, sent by vehicles with [`Capabilities::POWER`].
+    Power(PowerReading),
+    /// Phase of flight the vehicle's state machine is in, sent whenever it
+    /// changes and periodically in between by vehicles with
+    /// [`Capabilities::FLIGHT_PHASE`].
+    FlightPhase(FlightPhase),
+    /// State of every pyrotechnic channel, sent whenever it changes and
+    /// periodically in between by vehicles with [`Capabilities::PYRO`].
+    Pyro(heapless::Vec<PyroChannel, MAX_PYRO_CHANNELS>),
+    /// Response to a [`PacketUp::TimeSync`], sent by vehicles with [`Capabilities::TIME_SYNC`].
+    ///
+    /// Together with when the station receives it, this gives the four timestamps
+    /// of an NTP exchange.
+    TimeSync {
+        /// The `station_time` of the request being answered.
+        station_time: u64,
+        /// The vehicle's clock when the request arrived.
+        received: VehicleTime,
+        /// The vehicle's clock when the response was sent.
+        transmitted: VehicleTime,
+    },
+}
+
+/// Position fix from a GNSS receiver.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GnssFix {
+    /// Quality of the fix, the rest of the fields are meaningless without one.
+    pub fix: FixType,
+    /// Number of satellites used in the fix.
+    pub satellites: u8,
+    /// Latitude in 1e-7 degrees, positive to the north.
+    pub latitude: i32,
+    /// Longitude in 1e-7 degrees, positive to the east.
+    pub longitude: i32,
+    /// Altitude above mean sea level in mm.
+    pub altitude: i32,
+    /// Horizontal dilution of precision in hundredths.
+    pub hdop: u16,
+    /// Speed over ground in mm/s.
+    pub ground_speed: u32,
+    /// Course over ground in hundredths of a degree clockwise from true north.
+    pub course: u16,
+}
+
+/// Quality of a [`GnssFix`].
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FixType {
+    /// Not enough satellites are in view to determine a position.
+    NoFix,
+    /// Only latitude and longitude are known.
+    Fix2D,
+    /// Latitude, longitude and altitude are known.
+    Fix3D,
+}
+
+impl Display for FixType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            FixType::NoFix => write!(f, "No Fix"),
+            FixType::Fix2D => write!(f, "2D"),
+            FixType::Fix3D => write!(f, "3D"),
+        }
+    }
+}
+
+/// Reading from a barometric pressure sensor.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BarometerReading {
+    /// Static pressure in Pa.
+    pub pressure: u32,
+    /// Temperature of the sensor in hundredths of a *C.
+    pub temperature: i16,
+}
+
+/// Most battery cells a vehicle can report the voltages of.
+pub const MAX_CELLS: usize = 4;
+
+/// Reading from the vehicle's power monitor.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PowerReading {
+    /// Voltage of the main power bus in mV.
+    pub bus_voltage: u16,
+    /// Current drawn from the battery in mA, negative while it is charging.
+    pub current: i32,
+    /// Voltage of each cell of the battery in mV, if they are monitored.
+    pub cells: heapless::Vec<u16, MAX_CELLS>,
+    /// Estimated charge left in the battery in percent.
+    pub state_of_charge: u8,
+}
+
+/// Most pyrotechnic channels a vehicle can report the state of.
+pub const MAX_PYRO_CHANNELS: usize = 8;
+
+/// State of one of the vehicle's pyrotechnic channels.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PyroChannel {
+    /// Whether there is an unbroken circuit through the igniter.
+    pub continuity: bool,
+    /// Whether the channel will fire when told to.
+    pub armed: bool,
+    /// Whether the channel has been fired.
+    pub fired: bool,
 }
 
 /// Identification information about a vehicle
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct VehicleIdentification {
+    /// Protocol spoken by the vehicle, which must stay the first field.
+    pub protocol: Protocol,
     /// Vehicle name.
     pub name: heapless::String<32>,
     /// Vehicle firmware version.
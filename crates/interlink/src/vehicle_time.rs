@@ -19,7 +19,7 @@ impl VehicleTime {
     };
 
     /// Create a `VehicleTime`.
-    #[cfg(feature = "vehicle")]
+    #[cfg(any(feature = "vehicle", feature = "simulator"))]
     pub const fn new(seconds: u32, subsec_micros: u32) -> Self {
         Self {
             seconds,
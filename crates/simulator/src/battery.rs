@@ -0,0 +1,74 @@
+use std::time::Instant;
+
+use crate::profile::{FlightState, Phase};
+
+/// Cells in series in the battery.
+pub const CELLS: usize = 2;
+/// Resistance of each cell in Ω, which makes the voltage sag under load.
+const INTERNAL_RESISTANCE: f64 = 0.05;
+/// How far below the first cell each further cell sits in V, from the pack
+/// being slightly out of balance.
+const IMBALANCE: f64 = 0.015;
+
+/// Lithium polymer battery powering the vehicle.
+#[derive(Debug)]
+pub struct Battery {
+    /// Charge the battery holds when full in mAh.
+    capacity: f64,
+    /// Charge left in mAh.
+    charge: f64,
+    /// Current being drawn in mA.
+    pub current: f64,
+
+    last_update: Option<Instant>,
+}
+
+impl Battery {
+    /// Create a battery of `capacity` mAh that is `state_of_charge` percent full
+    pub fn new(capacity: f64, state_of_charge: f64) -> Self {
+        Self {
+            capacity,
+            charge: capacity * (state_of_charge / 100.0).clamp(0.0, 1.0),
+            current: 0.0,
+            last_update: None,
+        }
+    }
+
+    /// Drain the battery up to `now`
+    pub fn update(&mut self, state: &FlightState, now: Instant) {
+        let hours = self.last_update.map_or(0.0, |last_update| {
+            (now - last_update).as_secs_f64() / 3600.0
+        });
+        self.last_update = Some(now);
+
+        self.current = match state.phase {
+            Phase::Pad => 350.0,
+            // Servos and the radio working hardest
+            Phase::Boost | Phase::Coast => 600.0,
+            Phase::Descent => 450.0,
+        };
+
+        self.charge = (self.charge - self.current * hours).max(0.0);
+    }
+
+    /// Charge left as a fraction of the capacity
+    pub fn state_of_charge(&self) -> f64 {
+        self.charge / self.capacity
+    }
+
+    /// Voltage across each cell under the present load in V
+    pub fn cell_voltages(&self) -> [f64; CELLS] {
+        let state_of_charge = self.state_of_charge();
+
+        // Flat through most of the discharge, dropping off sharply once nearly empty
+        let open_circuit = 3.3 + 0.9 * state_of_charge - 0.15 * (1.0 - state_of_charge).powi(8);
+        let sag = self.current / 1000.0 * INTERNAL_RESISTANCE;
+
+        let mut cells = [0.0; CELLS];
+        for (index, cell) in cells.iter_mut().enumerate() {
+            *cell = open_circuit - sag - IMBALANCE * index as f64;
+        }
+
+        cells
+    }
+}
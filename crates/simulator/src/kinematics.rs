@@ -0,0 +1,98 @@
+use std::time::Instant;
+
+use crate::{
+    profile::{FlightState, Phase},
+    sensors::specific_force,
+};
+
+/// Standard gravity in m/s².
+const GRAVITY: f64 = 9.806_65;
+/// Speed the vehicle falls at under its parachute in m/s.
+const DESCENT_RATE: f64 = 30.0;
+/// How quickly the parachute slows the vehicle to its descent rate in seconds.
+const PARACHUTE_TIME_CONSTANT: f64 = 1.5;
+/// Horizontal speed of the vehicle while it is carried by the wind in m/s,
+/// as (east, north).
+const WIND: (f64, f64) = (4.0, 1.5);
+/// Horizontal speed of the vehicle while it is under power or coasting in m/s,
+/// as (east, north), from the launch rail being angled off vertical.
+const DOWNRANGE: (f64, f64) = (6.0, 2.0);
+
+/// Position and velocity of the vehicle relative to the launch pad.
+#[derive(Debug, Default)]
+pub struct Kinematics {
+    /// Height above the pad in m.
+    pub altitude: f64,
+    /// Upwards speed in m/s.
+    pub vertical_speed: f64,
+    /// Distance east of the pad in m.
+    pub east: f64,
+    /// Distance north of the pad in m.
+    pub north: f64,
+    /// Horizontal speed in m/s as (east, north).
+    pub horizontal_velocity: (f64, f64),
+
+    last_update: Option<Instant>,
+}
+
+impl Kinematics {
+    /// Advance the vehicle to `now`
+    pub fn update(&mut self, state: &FlightState, now: Instant) {
+        let dt = self
+            .last_update
+            .map_or(0.0, |last_update| (now - last_update).as_secs_f64());
+        self.last_update = Some(now);
+
+        match state.phase {
+            // Sitting on the pad, or landed wherever the wind took it
+            Phase::Pad => {
+                self.altitude = 0.0;
+                self.vertical_speed = 0.0;
+                self.horizontal_velocity = (0.0, 0.0);
+
+                if state.since_launch.is_none() {
+                    self.east = 0.0;
+                    self.north = 0.0;
+                }
+
+                return;
+            }
+            Phase::Boost | Phase::Coast => {
+                let (_, _, force) = specific_force(state);
+
+                self.vertical_speed += (force / 1000.0 - 1.0) * GRAVITY * dt;
+                self.horizontal_velocity = DOWNRANGE;
+            }
+            Phase::Descent => {
+                let approach = 1.0 - (-dt / PARACHUTE_TIME_CONSTANT).exp();
+
+                self.vertical_speed += (-DESCENT_RATE - self.vertical_speed) * approach;
+                self.horizontal_velocity = WIND;
+            }
+        }
+
+        self.altitude += self.vertical_speed * dt;
+        self.east += self.horizontal_velocity.0 * dt;
+        self.north += self.horizontal_velocity.1 * dt;
+
+        if self.altitude < 0.0 {
+            self.altitude = 0.0;
+            self.vertical_speed = 0.0;
+            self.horizontal_velocity = (0.0, 0.0);
+        }
+    }
+
+    /// Speed over the ground in m/s
+    pub fn ground_speed(&self) -> f64 {
+        self.horizontal_velocity.0.hypot(self.horizontal_velocity.1)
+    }
+
+    /// Direction of travel over the ground in degrees clockwise from north
+    pub fn course(&self) -> f64 {
+        self.horizontal_velocity
+            .0
+            .atan2(self.horizontal_velocity.1)
+            .to_degrees()
+            .rem_euclid(360.0)
+    }
+}
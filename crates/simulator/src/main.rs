@@ -0,0 +1,592 @@
+//! Simulated vehicle speaking the interlink protocol over a serial port.
+//!
+//! By default a pseudo-terminal is created for the ground station to connect to.
+#![forbid(unsafe_code)]
+#![deny(clippy::unwrap_used, clippy::trivially_copy_pass_by_ref)]
+#![warn(clippy::unwrap_in_result, clippy::missing_const_for_fn)]
+
+use std::{
+    io::{BufRead, BufReader, ErrorKind},
+    process, thread,
+    time::{Duration, Instant},
+};
+
+use battery::Battery;
+use flume::Sender;
+use interlink::{
+    phy,
+    proto::{
+        Capabilities, Command, CommandId, FlightPhase, NakReason, PacketDown, PacketDownData,
+        PacketUp, Protocol, PyroChannel, Sensor, SequenceNumber, VehicleIdentification,
+        MAX_PYRO_CHANNELS, PROTOCOL_VERSION,
+    },
+    vehicle_time::VehicleTime,
+};
+use kinematics::Kinematics;
+use profile::{FlightProfile, FlightState, Phase};
+use pyro::Pyros;
+use rand::Rng;
+use sensors::Sensors;
+use serialport::SerialPort;
+use structopt::StructOpt;
+use tracing::{debug, error, info, trace, warn};
+use tracing_subscriber::EnvFilter;
+
+mod battery;
+mod kinematics;
+mod profile;
+mod pyro;
+mod sensors;
+
+#[derive(Debug, StructOpt)]
+#[structopt(about = "Simulated vehicle speaking the interlink protocol")]
+struct Options {
+    /// Serial port to use instead of creating a pseudo-terminal
+    #[structopt(long)]
+    port: Option<String>,
+    /// Magnetometer packets per second
+    #[structopt(long, default_value = "20")]
+    magnetometer_rate: f64,
+    /// Accelerometer packets per second
+    #[structopt(long, default_value = "50")]
+    accelerometer_rate: f64,
+    /// eCompass temperature packets per second
+    #[structopt(long, default_value = "2")]
+    temperature_rate: f64,
+    /// GNSS fixes per second
+    #[structopt(long, default_value = "5")]
+    gnss_rate: f64,
+    /// Barometer packets per second
+    #[structopt(long, default_value = "20")]
+    barometer_rate: f64,
+    /// Gyroscope packets per second
+    #[structopt(long, default_value = "50")]
+    gyroscope_rate: f64,
+    /// Power packets per second
+    #[structopt(long, default_value = "2")]
+    power_rate: f64,
+    /// Capacity of the battery in mAh
+    #[structopt(long, default_value = "1000")]
+    battery_capacity: f64,
+    /// Charge the battery starts with in percent, lower to test low voltage alarms
+    #[structopt(long, default_value = "100")]
+    battery_charge: f64,
+    /// Number of pyrotechnic channels
+    #[structopt(long, default_value = "2")]
+    pyro_channels: usize,
+    /// Channel left without an igniter, to test how the ground station shows
+    /// a lack of continuity
+    #[structopt(long)]
+    open_pyro: Option<u8>,
+    /// Multiplier applied to the standard deviation of all sensor noise
+    #[structopt(long, default_value = "1")]
+    noise: f64,
+    /// Probability of any single packet being dropped
+    #[structopt(long, default_value = "0")]
+    dropout: f64,
+    /// Flight profile as comma separated `phase:seconds` pairs, starting when
+    /// the ground station first connects
+    #[structopt(long, default_value = "pad:10,boost:3,coast:8,descent:30")]
+    profile: FlightProfile,
+    /// Restart the flight profile once it finishes instead of staying landed
+    #[structopt(long)]
+    repeat: bool,
+    /// Claim to speak another protocol version, to test how the ground station
+    /// handles a mismatch
+    #[structopt(long)]
+    protocol_version: Option<u16>,
+}
+
+/// How often the flight phase is repeated while it stays the same.
+const PHASE_REPORT_INTERVAL: Duration = Duration::from_secs(1);
+/// How often the state of the pyro channels is repeated while it stays the same.
+const PYRO_REPORT_INTERVAL: Duration = Duration::from_secs(1);
+/// How long the flight computer reports apogee before recovery deploys.
+const APOGEE_DWELL: Duration = Duration::from_secs(1);
+
+/// Highest telemetry rate the ground station may ask for.
+const MAX_TELEMETRY_RATE: u16 = 1000;
+
+/// A sensor streamed to the ground station at a fixed rate.
+#[derive(Debug)]
+struct Stream {
+    sensor: Sensor,
+    /// Time between packets, or `None` if the sensor is not being sent
+    interval: Option<Duration>,
+    next: Instant,
+}
+
+impl Stream {
+    fn new(sensor: Sensor, rate: f64, now: Instant) -> Self {
+        Self {
+            sensor,
+            interval: (rate > 0.0).then(|| Duration::from_secs_f64(1.0 / rate)),
+            next: now,
+        }
+    }
+}
+
+/// State of the simulated vehicle that the ground station can command.
+#[derive(Debug)]
+struct Vehicle {
+    /// When the vehicle booted, which vehicle time is measured from
+    epoch: Instant,
+    /// When the flight profile started
+    launch_epoch: Option<Instant>,
+    armed: bool,
+    streams: Vec<Stream>,
+    /// Sequence number of the next packet sent
+    sequence: SequenceNumber,
+    kinematics: Kinematics,
+    battery: Battery,
+    /// Flight phase last sent and when to repeat it
+    reported_phase: Option<(FlightPhase, Instant)>,
+    pyros: Pyros,
+    /// Pyro channel states last sent and when to repeat them
+    reported_pyros: Option<(heapless::Vec<PyroChannel, MAX_PYRO_CHANNELS>, Instant)>,
+}
+
+impl Vehicle {
+    fn boot(options: &Options) -> Self {
+        let epoch = Instant::now();
+
+        Self {
+            epoch,
+            launch_epoch: None,
+            armed: false,
+            streams: vec![
+                Stream::new(Sensor::Magnetometer, options.magnetometer_rate, epoch),
+                Stream::new(Sensor::Accelerometer, options.accelerometer_rate, epoch),
+                Stream::new(Sensor::ECompassTemperature, options.temperature_rate, epoch),
+                Stream::new(Sensor::Gnss, options.gnss_rate, epoch),
+                Stream::new(Sensor::Barometer, options.barometer_rate, epoch),
+                Stream::new(Sensor::Gyroscope, options.gyroscope_rate, epoch),
+                Stream::new(Sensor::Power, options.power_rate, epoch),
+            ],
+            kinematics: Kinematics::default(),
+            battery: Battery::new(options.battery_capacity, options.battery_charge),
+            reported_phase: None,
+            pyros: Pyros::new(options.pyro_channels, options.open_pyro),
+            reported_pyros: None,
+            sequence: 0,
+        }
+    }
+
+    fn flight_state(&self, options: &Options, now: Instant) -> FlightState {
+        let mut elapsed = self
+            .launch_epoch
+            .map_or(Duration::ZERO, |launch_epoch| now - launch_epoch);
+
+        if options.repeat && !options.profile.duration().is_zero() {
+            elapsed = Duration::from_secs_f64(
+                elapsed.as_secs_f64() % options.profile.duration().as_secs_f64(),
+            );
+        }
+
+        options.profile.state_at(elapsed)
+    }
+
+    /// Phase the flight computer's state machine would be in
+    fn flight_phase(&self, state: &FlightState) -> FlightPhase {
+        match state.phase {
+            Phase::Pad if state.since_launch.is_some() => FlightPhase::Landed,
+            Phase::Pad if self.armed => FlightPhase::Armed,
+            Phase::Pad => FlightPhase::Idle,
+            Phase::Boost => FlightPhase::Boost,
+            Phase::Coast if self.kinematics.vertical_speed <= 0.0 => FlightPhase::Apogee,
+            Phase::Coast => FlightPhase::Coast,
+            Phase::Descent if state.in_phase < APOGEE_DWELL => FlightPhase::Apogee,
+            Phase::Descent if self.kinematics.altitude <= 0.0 => FlightPhase::Landed,
+            Phase::Descent => FlightPhase::Descent,
+        }
+    }
+
+    /// Time since the vehicle booted, by its own clock
+    fn time(&self) -> VehicleTime {
+        let elapsed = self.epoch.elapsed();
+
+        VehicleTime::new(elapsed.as_secs() as u32, elapsed.subsec_micros())
+    }
+
+    /// Number the next packet sent
+    const fn next_sequence(&mut self) -> SequenceNumber {
+        let sequence = self.sequence;
+        self.sequence = self.sequence.wrapping_add(1);
+
+        sequence
+    }
+
+    /// Carry out a command, returning the response to send
+    fn command(&mut self, options: &Options, id: CommandId, command: Command) -> PacketDownData {
+        let in_flight = self.flight_state(options, Instant::now()).phase != Phase::Pad;
+
+        match command {
+            Command::Arm | Command::Disarm if in_flight => PacketDownData::Nak {
+                id,
+                reason: NakReason::InvalidState,
+            },
+            Command::Arm => {
+                self.armed = true;
+                info!("Armed");
+
+                PacketDownData::Ack(id)
+            }
+            Command::Disarm => {
+                self.armed = false;
+                info!("Disarmed");
+
+                PacketDownData::Ack(id)
+            }
+            Command::Ping { nonce } => PacketDownData::Pong { id, nonce },
+            // Rebooting and identifying are handled by the caller once acknowledged
+            Command::Reboot | Command::RequestIdentification => PacketDownData::Ack(id),
+            Command::SetTelemetryRate { rate, .. } if rate > MAX_TELEMETRY_RATE => {
+                PacketDownData::Nak {
+                    id,
+                    reason: NakReason::InvalidArgument,
+                }
+            }
+            Command::SetTelemetryRate { sensor, rate } => {
+                let now = Instant::now();
+
+                for stream in self
+                    .streams
+                    .iter_mut()
+                    .filter(|stream| stream.sensor == sensor)
+                {
+                    *stream = Stream::new(sensor, f64::from(rate), now);
+                }
+
+                info!(%sensor, rate, "Changed telemetry rate");
+
+                PacketDownData::Ack(id)
+            }
+            Command::FirePyro { channel } => match self.pyros.fire(channel, self.armed) {
+                Ok(()) => {
+                    info!(channel, "Fired pyro channel");
+
+                    PacketDownData::Ack(id)
+                }
+                Err(reason) => {
+                    warn!(channel, %reason, "Refused to fire pyro channel");
+
+                    PacketDownData::Nak { id, reason }
+                }
+            },
+        }
+    }
+}
+
+fn hello(options: &Options) -> PacketDownData {
+    PacketDownData::Hello(VehicleIdentification {
+        protocol: Protocol {
+            version: options.protocol_version.unwrap_or(PROTOCOL_VERSION),
+            capabilities: Capabilities::COMMANDS
+                | Capabilities::GNSS
+                | Capabilities::BAROMETER
+                | Capabilities::GYROSCOPE
+                | Capabilities::POWER
+                | Capabilities::FLIGHT_PHASE
+                | Capabilities::PYRO
+                | Capabilities::TIME_SYNC,
+        },
+        name: heapless::String::from("Simulator"),
+        version: heapless::String::from(env!("CARGO_PKG_VERSION")),
+    })
+}
+
+pub fn main() {
+    dotenv::dotenv().ok();
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::from_default_env())
+        .init();
+
+    let options = Options::from_args();
+
+    if !(0.0..=1.0).contains(&options.dropout) {
+        error!(dropout = options.dropout, "Dropout must be between 0 and 1");
+        process::exit(1);
+    }
+
+    if options.pyro_channels > MAX_PYRO_CHANNELS {
+        error!(
+            channels = options.pyro_channels,
+            max = MAX_PYRO_CHANNELS,
+            "Too many pyro channels"
+        );
+        process::exit(1);
+    }
+
+    // The pseudo-terminal's slave end must stay open for the master end to be usable
+    let (mut port, _slave) = match open_port(options.port.as_deref()) {
+        Ok(port) => port,
+        Err(error) => {
+            error!(%error, "Unable to open serial port");
+            process::exit(1);
+        }
+    };
+
+    let reader = match port.try_clone() {
+        Ok(reader) => reader,
+        Err(error) => {
+            error!(%error, "Unable to clone serial port");
+            process::exit(1);
+        }
+    };
+
+    let (sender, receiver) = flume::unbounded();
+    thread::spawn(move || uplink_listener(reader, sender));
+
+    let mut vehicle = Vehicle::boot(&options);
+    let mut sensors = Sensors::new(options.noise);
+    let mut rng = rand::thread_rng();
+
+    loop {
+        for packet in receiver.try_iter() {
+            match packet {
+                PacketUp::Welcome(protocol) => {
+                    info!("Ground station connected");
+
+                    if protocol.version != PROTOCOL_VERSION {
+                        warn!(
+                            station = protocol.version,
+                            vehicle = PROTOCOL_VERSION,
+                            "Ground station speaks a different protocol version"
+                        );
+                    }
+
+                    vehicle.launch_epoch.get_or_insert_with(Instant::now);
+
+                    send(port.as_mut(), &mut vehicle, hello(&options));
+                }
+                PacketUp::Command { id, command } => {
+                    info!(id, ?command, "Received command");
+
+                    let response = vehicle.command(&options, id, command);
+                    send(port.as_mut(), &mut vehicle, response);
+
+                    match command {
+                        Command::Reboot => {
+                            info!("Rebooting");
+
+                            // The ground station stays connected, so announce ourselves again
+                            vehicle = Vehicle::boot(&options);
+                            vehicle.launch_epoch = Some(vehicle.epoch);
+                            send(port.as_mut(), &mut vehicle, hello(&options));
+                        }
+                        Command::RequestIdentification => {
+                            send(port.as_mut(), &mut vehicle, hello(&options))
+                        }
+                        _ => {}
+                    }
+                }
+                PacketUp::TimeSync { station_time } => {
+                    let received = vehicle.time();
+
+                    trace!(station_time, "Received time sync request");
+
+                    let response = PacketDownData::TimeSync {
+                        station_time,
+                        received,
+                        transmitted: vehicle.time(),
+                    };
+                    send(port.as_mut(), &mut vehicle, response);
+                }
+            }
+        }
+
+        // Only stream once the ground station has said hello
+        if vehicle.launch_epoch.is_none() {
+            thread::sleep(Duration::from_millis(10));
+            continue;
+        }
+
+        let now = Instant::now();
+        let state = vehicle.flight_state(&options, now);
+        vehicle.kinematics.update(&state, now);
+        vehicle.battery.update(&state, now);
+
+        let phase = vehicle.flight_phase(&state);
+        let report_phase = match vehicle.reported_phase {
+            Some((reported, repeat_at)) => reported != phase || now >= repeat_at,
+            None => true,
+        };
+
+        if report_phase {
+            if vehicle.reported_phase.map(|(reported, _)| reported) != Some(phase) {
+                info!(%phase, "Flight phase changed");
+            }
+
+            vehicle.reported_phase = Some((phase, now + PHASE_REPORT_INTERVAL));
+            send(
+                port.as_mut(),
+                &mut vehicle,
+                PacketDownData::FlightPhase(phase),
+            );
+        }
+
+        let pyros = vehicle.pyros.status(vehicle.armed);
+        let report_pyros = match &vehicle.reported_pyros {
+            Some((reported, repeat_at)) => *reported != pyros || now >= *repeat_at,
+            None => true,
+        };
+
+        if report_pyros {
+            vehicle.reported_pyros = Some((pyros.clone(), now + PYRO_REPORT_INTERVAL));
+            send(port.as_mut(), &mut vehicle, PacketDownData::Pyro(pyros));
+        }
+
+        let due = vehicle
+            .streams
+            .iter_mut()
+            .filter(|stream| stream.next <= now)
+            .filter_map(|stream| {
+                let interval = stream.interval?;
+
+                stream.next += interval;
+
+                // Skip ahead rather than bursting if we have fallen behind
+                if stream.next < now {
+                    stream.next = now + interval;
+                }
+
+                Some(stream.sensor)
+            })
+            .collect::<Vec<_>>();
+
+        for sensor in due {
+            if rng.gen_bool(options.dropout) {
+                // Lost in transit, so the packet was still numbered
+                let sequence = vehicle.next_sequence();
+                trace!(?sensor, sequence, "Dropping packet");
+                continue;
+            }
+
+            let data = match sensor {
+                Sensor::Magnetometer => PacketDownData::Magnetometer(sensors.magnetometer(&state)),
+                Sensor::Accelerometer => {
+                    PacketDownData::Accelerometer(sensors.accelerometer(&state))
+                }
+                Sensor::ECompassTemperature => {
+                    PacketDownData::ECompassTemperature(sensors.temperature(&state))
+                }
+                Sensor::Gnss => PacketDownData::Gnss(sensors.gnss(&vehicle.kinematics)),
+                Sensor::Barometer => {
+                    PacketDownData::Barometer(sensors.barometer(&vehicle.kinematics))
+                }
+                Sensor::Gyroscope => PacketDownData::Gyroscope(sensors.gyroscope(&state)),
+                Sensor::Power => PacketDownData::Power(sensors.power(&vehicle.battery)),
+            };
+
+            send(port.as_mut(), &mut vehicle, data);
+        }
+
+        let next = vehicle
+            .streams
+            .iter()
+            .filter(|stream| stream.interval.is_some())
+            .map(|stream| stream.next)
+            .min()
+            .unwrap_or(now + Duration::from_millis(10));
+
+        // Wake up regularly to answer the ground station
+        thread::sleep(
+            next.saturating_duration_since(Instant::now())
+                .min(Duration::from_millis(10)),
+        );
+    }
+}
+
+/// The port to talk over, and the slave end of a pseudo-terminal if one was created
+type OpenPort = (Box<dyn SerialPort>, Option<Box<dyn SerialPort>>);
+
+/// Open the requested port, or create a pseudo-terminal if none was given
+fn open_port(port: Option<&str>) -> serialport::Result<OpenPort> {
+    match port {
+        Some(port) => {
+            let port = serialport::new(port, 0)
+                .timeout(Duration::from_millis(10))
+                .open()?;
+
+            info!("Simulating vehicle on {}", port.name().unwrap_or_default());
+
+            Ok((port, None))
+        }
+        #[cfg(unix)]
+        None => {
+            let (mut master, slave) = serialport::TTYPort::pair()?;
+            master.set_timeout(Duration::from_millis(10))?;
+
+            let name = slave.name().unwrap_or_default();
+            info!("Simulating vehicle on {}", name);
+            info!("Start the ground station with SERIAL_PORT={}", name);
+
+            Ok((Box::new(master), Some(Box::new(slave))))
+        }
+        #[cfg(not(unix))]
+        None => Err(serialport::Error::new(
+            serialport::ErrorKind::InvalidInput,
+            "pseudo-terminals are not supported on this platform, use --port",
+        )),
+    }
+}
+
+fn send(port: &mut dyn SerialPort, vehicle: &mut Vehicle, data: PacketDownData) {
+    let packet = PacketDown {
+        sequence: vehicle.next_sequence(),
+        time: vehicle.time(),
+        data,
+    };
+
+    let mut buffer = [0; phy::serial::BUFFER_SIZE];
+    let frame = phy::frame::encode(&packet, &mut buffer).expect("unable to serialize PacketDown");
+
+    match port.write_all(frame) {
+        Ok(()) => {}
+        Err(error) if error.kind() == ErrorKind::TimedOut => {
+            // Nobody is reading from the other end
+            trace!("Timed out writing packet");
+        }
+        Err(error) => {
+            error!(%error, "Failed to write to serial port");
+            process::exit(1);
+        }
+    }
+}
+
+fn uplink_listener(port: Box<dyn SerialPort>, sender: Sender<PacketUp>) {
+    trace!("Uplink listener spawned");
+
+    let mut data_storage = Vec::with_capacity(phy::serial::BUFFER_SIZE);
+    let mut buffered_port = BufReader::new(port);
+
+    loop {
+        match buffered_port.read_until(phy::serial::COBS_SENTINEL, &mut data_storage) {
+            Ok(0) => {
+                thread::sleep(Duration::from_millis(10));
+                continue;
+            }
+            Ok(_) => {}
+            Err(error) if error.kind() == ErrorKind::TimedOut => continue,
+            Err(error) => {
+                warn!(%error, "Failed to read from serial port");
+                thread::sleep(Duration::from_millis(100));
+                continue;
+            }
+        };
+
+        // A timed out read leaves a partial frame in the buffer, so decode all of it
+        match phy::frame::decode::<PacketUp>(&mut data_storage) {
+            Ok(packet) => {
+                debug!(?packet, "Received packet");
+
+                if sender.send(packet).is_err() {
+                    return;
+                }
+            }
+            Err(error) => warn!(%error, "Failed to deserialize packet"),
+        }
+
+        data_storage.clear();
+    }
+}
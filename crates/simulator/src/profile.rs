@@ -0,0 +1,151 @@
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+    time::Duration,
+};
+
+/// Phase of a simulated flight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// Sitting still on the launch pad.
+    Pad,
+    /// Motor burning.
+    Boost,
+    /// Ballistic flight after burnout.
+    Coast,
+    /// Falling under a parachute.
+    Descent,
+}
+
+impl Display for Phase {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Phase::Pad => write!(f, "pad"),
+            Phase::Boost => write!(f, "boost"),
+            Phase::Coast => write!(f, "coast"),
+            Phase::Descent => write!(f, "descent"),
+        }
+    }
+}
+
+impl FromStr for Phase {
+    type Err = ProfileError;
+
+    fn from_str(phase: &str) -> Result<Self, Self::Err> {
+        match phase {
+            "pad" => Ok(Phase::Pad),
+            "boost" => Ok(Phase::Boost),
+            "coast" => Ok(Phase::Coast),
+            "descent" => Ok(Phase::Descent),
+            _ => Err(ProfileError::UnknownPhase(phase.to_string())),
+        }
+    }
+}
+
+/// Scripted sequence of flight phases.
+#[derive(Debug, Clone)]
+pub struct FlightProfile {
+    phases: Vec<(Phase, Duration)>,
+}
+
+/// Position within a [`FlightProfile`].
+#[derive(Debug, Clone, Copy)]
+pub struct FlightState {
+    pub phase: Phase,
+    /// Time spent in the current phase.
+    pub in_phase: Duration,
+    /// Time since the start of the first phase that is not [`Phase::Pad`].
+    pub since_launch: Option<Duration>,
+}
+
+impl FlightProfile {
+    pub fn duration(&self) -> Duration {
+        self.phases.iter().map(|&(_, duration)| duration).sum()
+    }
+
+    /// Find where in the profile `elapsed` falls
+    ///
+    /// Once the profile has run out the vehicle is considered landed, which
+    /// looks the same as sitting on the pad.
+    pub fn state_at(&self, elapsed: Duration) -> FlightState {
+        let mut phase_start = Duration::ZERO;
+        let mut launch = None;
+
+        for &(phase, duration) in &self.phases {
+            if phase != Phase::Pad && launch.is_none() {
+                launch = Some(phase_start);
+            }
+
+            if elapsed < phase_start + duration {
+                return FlightState {
+                    phase,
+                    in_phase: elapsed - phase_start,
+                    since_launch: launch.map(|launch| elapsed - launch),
+                };
+            }
+
+            phase_start += duration;
+        }
+
+        FlightState {
+            phase: Phase::Pad,
+            in_phase: elapsed - phase_start,
+            since_launch: launch.map(|launch| elapsed - launch),
+        }
+    }
+}
+
+impl FromStr for FlightProfile {
+    type Err = ProfileError;
+
+    /// Parse a profile from comma separated `phase:seconds` pairs
+    fn from_str(profile: &str) -> Result<Self, Self::Err> {
+        let phases = profile
+            .split(',')
+            .map(|step| {
+                let (phase, seconds) = step
+                    .split_once(':')
+                    .ok_or_else(|| ProfileError::MalformedStep(step.to_string()))?;
+
+                let seconds = seconds
+                    .trim()
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|seconds| seconds.is_finite() && *seconds >= 0.0)
+                    .ok_or_else(|| ProfileError::InvalidDuration(seconds.to_string()))?;
+
+                Ok((phase.trim().parse()?, Duration::from_secs_f64(seconds)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self { phases })
+    }
+}
+
+#[derive(Debug)]
+pub enum ProfileError {
+    MalformedStep(String),
+    UnknownPhase(String),
+    InvalidDuration(String),
+}
+
+impl Display for ProfileError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ProfileError::MalformedStep(step) => {
+                write!(f, "expected `phase:seconds`, found `{}`", step)
+            }
+            ProfileError::UnknownPhase(phase) => write!(
+                f,
+                "unknown phase `{}`, expected one of pad, boost, coast or descent",
+                phase
+            ),
+            ProfileError::InvalidDuration(duration) => {
+                write!(f, "`{}` is not a valid number of seconds", duration)
+            }
+        }
+    }
+}
+
+impl Error for ProfileError {}
@@ -0,0 +1,62 @@
+use interlink::proto::{NakReason, PyroChannel, MAX_PYRO_CHANNELS};
+
+/// An igniter wired to one of the pyrotechnic channels.
+#[derive(Debug, Clone, Copy)]
+struct Igniter {
+    /// Whether current can flow through the igniter's bridge wire.
+    continuity: bool,
+    fired: bool,
+}
+
+/// Pyrotechnic channels deploying the vehicle's recovery system.
+#[derive(Debug)]
+pub struct Pyros {
+    igniters: Vec<Igniter>,
+}
+
+impl Pyros {
+    /// Create `channels` channels with an igniter wired to each, except for
+    /// the `open` channel if there is one
+    pub fn new(channels: usize, open: Option<u8>) -> Self {
+        Self {
+            igniters: (0..channels)
+                .map(|channel| Igniter {
+                    continuity: open.map(usize::from) != Some(channel),
+                    fired: false,
+                })
+                .collect(),
+        }
+    }
+
+    /// Fire a channel, which only works while the vehicle is `armed` and the
+    /// channel's igniter is intact
+    pub fn fire(&mut self, channel: u8, armed: bool) -> Result<(), NakReason> {
+        let igniter = self
+            .igniters
+            .get_mut(usize::from(channel))
+            .ok_or(NakReason::InvalidArgument)?;
+
+        if !armed || igniter.fired || !igniter.continuity {
+            return Err(NakReason::InvalidState);
+        }
+
+        // The bridge wire burns through as the igniter goes off
+        igniter.fired = true;
+        igniter.continuity = false;
+
+        Ok(())
+    }
+
+    /// State of every channel as reported to the ground station
+    pub fn status(&self, armed: bool) -> heapless::Vec<PyroChannel, MAX_PYRO_CHANNELS> {
+        self.igniters
+            .iter()
+            .take(MAX_PYRO_CHANNELS)
+            .map(|igniter| PyroChannel {
+                continuity: igniter.continuity,
+                armed,
+                fired: igniter.fired,
+            })
+            .collect()
+    }
+}
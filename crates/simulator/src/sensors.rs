@@ -0,0 +1,244 @@
+use interlink::proto::{BarometerReading, FixType, GnssFix, PowerReading, Vector3};
+use rand::{rngs::ThreadRng, Rng};
+use rand_distr::StandardNormal;
+
+use crate::{
+    battery::Battery,
+    kinematics::Kinematics,
+    profile::{FlightState, Phase},
+};
+
+/// Standard deviation of accelerometer noise in mg.
+const ACCELEROMETER_NOISE: f64 = 15.0;
+/// Standard deviation of magnetometer noise in nT.
+const MAGNETOMETER_NOISE: f64 = 250.0;
+/// Standard deviation of gyroscope noise in mdps.
+const GYROSCOPE_NOISE: f64 = 200.0;
+/// Standard deviation of voltage noise in V.
+const VOLTAGE_NOISE: f64 = 0.005;
+/// Standard deviation of current noise in mA.
+const CURRENT_NOISE: f64 = 10.0;
+/// Standard deviation of temperature noise in *C.
+const TEMPERATURE_NOISE: f64 = 0.05;
+
+/// Horizontal component of the earth's magnetic field in nT.
+const FIELD_HORIZONTAL: f64 = 20_000.0;
+/// Vertical component of the earth's magnetic field in nT, pointing down.
+const FIELD_VERTICAL: f64 = 45_000.0;
+
+/// Temperature of the vehicle before launch in *C.
+const AMBIENT_TEMPERATURE: f64 = 22.0;
+
+/// Standard deviation of GNSS horizontal position noise in m.
+const GNSS_HORIZONTAL_NOISE: f64 = 1.5;
+/// Standard deviation of GNSS altitude noise in m.
+const GNSS_VERTICAL_NOISE: f64 = 3.0;
+
+/// Standard deviation of barometer pressure noise in Pa.
+const PRESSURE_NOISE: f64 = 3.0;
+
+/// Pressure at mean sea level in Pa.
+const SEA_LEVEL_PRESSURE: f64 = 101_325.0;
+/// Drop in air temperature with altitude in *C/m.
+const LAPSE_RATE: f64 = 0.0065;
+
+/// Latitude of the launch pad in degrees.
+const PAD_LATITUDE: f64 = 32.990_254;
+/// Longitude of the launch pad in degrees.
+const PAD_LONGITUDE: f64 = -106.974_998;
+/// Altitude of the launch pad above mean sea level in m.
+const PAD_ALTITUDE: f64 = 1401.0;
+/// Mean radius of the earth in m.
+const EARTH_RADIUS: f64 = 6_371_000.0;
+
+/// Synthetic sensors of a vehicle following a flight profile.
+#[derive(Debug)]
+pub struct Sensors {
+    rng: ThreadRng,
+    noise: f64,
+}
+
+impl Sensors {
+    /// Create sensors with noise scaled by `noise`
+    pub fn new(noise: f64) -> Self {
+        Self {
+            rng: rand::thread_rng(),
+            noise,
+        }
+    }
+
+    fn noise(&mut self, standard_deviation: f64) -> f64 {
+        self.rng.sample::<f64, _>(StandardNormal) * standard_deviation * self.noise
+    }
+
+    /// Specific force felt by the vehicle in mg, with Z pointing up the airframe
+    pub fn accelerometer(&mut self, state: &FlightState) -> Vector3<i32> {
+        let (x, y, z) = specific_force(state);
+
+        // Motor vibration while boosting
+        let vibration = if state.phase == Phase::Boost {
+            8.0
+        } else {
+            1.0
+        };
+
+        Vector3 {
+            x: (x + self.noise(ACCELEROMETER_NOISE * vibration)) as i32,
+            y: (y + self.noise(ACCELEROMETER_NOISE * vibration)) as i32,
+            z: (z + self.noise(ACCELEROMETER_NOISE * vibration)) as i32,
+        }
+    }
+
+    /// Earth's magnetic field in nT as seen by the spinning vehicle
+    pub fn magnetometer(&mut self, state: &FlightState) -> Vector3<i32> {
+        let roll = roll_angle(state);
+
+        Vector3 {
+            x: (FIELD_HORIZONTAL * roll.cos() + self.noise(MAGNETOMETER_NOISE)) as i32,
+            y: (-FIELD_HORIZONTAL * roll.sin() + self.noise(MAGNETOMETER_NOISE)) as i32,
+            z: (-FIELD_VERTICAL + self.noise(MAGNETOMETER_NOISE)) as i32,
+        }
+    }
+
+    /// Angular rate in mdps of the vehicle spinning about its long axis
+    pub fn gyroscope(&mut self, state: &FlightState) -> Vector3<i32> {
+        let roll_rate = roll_rate(state).to_degrees() * 1000.0;
+
+        Vector3 {
+            x: self.noise(GYROSCOPE_NOISE) as i32,
+            y: self.noise(GYROSCOPE_NOISE) as i32,
+            z: (roll_rate + self.noise(GYROSCOPE_NOISE)) as i32,
+        }
+    }
+
+    /// Position fix of the vehicle as it drifts away from the pad
+    pub fn gnss(&mut self, kinematics: &Kinematics) -> GnssFix {
+        let north = kinematics.north + self.noise(GNSS_HORIZONTAL_NOISE);
+        let east = kinematics.east + self.noise(GNSS_HORIZONTAL_NOISE);
+        let altitude = PAD_ALTITUDE + kinematics.altitude + self.noise(GNSS_VERTICAL_NOISE);
+
+        let latitude = PAD_LATITUDE + (north / EARTH_RADIUS).to_degrees();
+        let longitude =
+            PAD_LONGITUDE + (east / (EARTH_RADIUS * PAD_LATITUDE.to_radians().cos())).to_degrees();
+
+        GnssFix {
+            fix: FixType::Fix3D,
+            satellites: self.rng.gen_range(8..=12),
+            latitude: (latitude * 1e7) as i32,
+            longitude: (longitude * 1e7) as i32,
+            altitude: (altitude * 1000.0) as i32,
+            hdop: self.rng.gen_range(80..=120),
+            ground_speed: (kinematics.ground_speed() * 1000.0) as u32,
+            course: (kinematics.course() * 100.0) as u16,
+        }
+    }
+
+    /// Static pressure in the international standard atmosphere, with the
+    /// sensor reading the outside air temperature
+    pub fn barometer(&mut self, kinematics: &Kinematics) -> BarometerReading {
+        let altitude = PAD_ALTITUDE + kinematics.altitude;
+        let pressure = SEA_LEVEL_PRESSURE * (1.0 - altitude / 44_330.77).powf(5.255_88)
+            + self.noise(PRESSURE_NOISE);
+        let temperature =
+            AMBIENT_TEMPERATURE - kinematics.altitude * LAPSE_RATE + self.noise(TEMPERATURE_NOISE);
+
+        BarometerReading {
+            pressure: pressure as u32,
+            temperature: (temperature * 100.0) as i16,
+        }
+    }
+
+    /// Battery and bus measured by the power monitor
+    pub fn power(&mut self, battery: &Battery) -> PowerReading {
+        let cells = battery.cell_voltages();
+
+        let mut reading = PowerReading {
+            bus_voltage: ((cells.iter().sum::<f64>() + self.noise(VOLTAGE_NOISE)) * 1000.0) as u16,
+            current: (battery.current + self.noise(CURRENT_NOISE)) as i32,
+            cells: heapless::Vec::new(),
+            state_of_charge: (battery.state_of_charge() * 100.0).round() as u8,
+        };
+
+        for cell in cells {
+            // Never more cells than the protocol allows
+            let _ = reading
+                .cells
+                .push(((cell + self.noise(VOLTAGE_NOISE)) * 1000.0) as u16);
+        }
+
+        reading
+    }
+
+    /// eCompass die temperature in *C, warmed by the motor
+    pub fn temperature(&mut self, state: &FlightState) -> f32 {
+        let heating = match state.since_launch {
+            Some(since_launch) => {
+                let seconds = since_launch.as_secs_f64();
+
+                // Heat soaks in from the motor then slowly dissipates
+                15.0 * (1.0 - (-seconds / 4.0).exp()) * (-seconds / 60.0).exp()
+            }
+            None => 0.0,
+        };
+
+        (AMBIENT_TEMPERATURE + heating + self.noise(TEMPERATURE_NOISE)) as f32
+    }
+}
+
+/// Specific force on the vehicle in mg without any noise, with Z pointing up the airframe
+pub fn specific_force(state: &FlightState) -> (f64, f64, f64) {
+    let seconds = state.in_phase.as_secs_f64();
+
+    match state.phase {
+        Phase::Pad => (0.0, 0.0, 1000.0),
+        // Thrust ramps up quickly then tails off as the motor burns out
+        Phase::Boost => {
+            let thrust = 6000.0 * (1.0 - (-seconds * 8.0).exp()) - 300.0 * seconds;
+
+            (0.0, 0.0, 1000.0 + thrust.max(0.0))
+        }
+        // Drag decelerates the vehicle, dying off as it slows towards apogee
+        Phase::Coast => (0.0, 0.0, -400.0 * (-seconds / 3.0).exp()),
+        // Swinging under the parachute
+        Phase::Descent => (
+            150.0 * (seconds * 1.3).sin(),
+            100.0 * (seconds * 0.9).cos(),
+            1000.0,
+        ),
+    }
+}
+
+/// Rotation of the vehicle about its long axis in radians
+fn roll_angle(state: &FlightState) -> f64 {
+    match (state.phase, state.since_launch) {
+        (Phase::Pad, _) | (_, None) => 0.0,
+        // Fins spin the vehicle up during powered and ballistic flight
+        (Phase::Boost | Phase::Coast, Some(since_launch)) => {
+            ballistic_roll_angle(since_launch.as_secs_f64())
+        }
+        // Slow rotation while hanging from the parachute, carrying on from where the fins left it
+        (Phase::Descent, Some(since_launch)) => {
+            let deployment = since_launch.saturating_sub(state.in_phase);
+
+            ballistic_roll_angle(deployment.as_secs_f64()) + 0.4 * state.in_phase.as_secs_f64()
+        }
+    }
+}
+
+/// Rotation in radians from the fins, `seconds` after launch
+fn ballistic_roll_angle(seconds: f64) -> f64 {
+    1.5 * seconds * seconds / (1.0 + seconds)
+}
+
+/// Rate of rotation of the vehicle about its long axis in radians per second
+fn roll_rate(state: &FlightState) -> f64 {
+    match (state.phase, state.since_launch) {
+        (Phase::Pad, _) | (_, None) => 0.0,
+        (Phase::Boost | Phase::Coast, Some(since_launch)) => {
+            let seconds = since_launch.as_secs_f64();
+
+            1.5 * seconds * (seconds + 2.0) / ((1.0 + seconds) * (1.0 + seconds))
+        }
+        (Phase::Descent, Some(_)) => 0.4,
+    }
+}
@@ -0,0 +1,131 @@
+//! Orientation of the vehicle, fused from its accelerometer, magnetometer and gyroscope
+//!
+//! Orientations are relative to a north-west-up frame, so a vehicle standing
+//! on the pad with its X axis pointing north has no rotation at all.
+
+use interlink::proto::Vector3;
+
+use crate::time_manager::unit::VehicleTime;
+
+use self::quaternion::{cross, normalize, Quaternion, Vector};
+
+pub mod quaternion;
+
+/// How strongly the accelerometer and magnetometer pull the estimate back
+/// from the integrated gyroscope, in radians per second per unit of error
+const CORRECTION_GAIN: f64 = 1.0;
+/// Accelerometer readings further than this from 1g in mg are not taken as
+/// pointing up, as the vehicle is under thrust or drag
+const ACCELERATION_TOLERANCE: f64 = 150.0;
+/// Time between gyroscope readings, in seconds, beyond which the orientation
+/// is started over instead of integrated
+const MAX_STEP: f64 = 0.5;
+
+/// Combines readings from all of the vehicle's motion sensors into a single
+/// orientation using a complementary filter
+///
+/// The gyroscope is integrated for short term changes, and the accelerometer
+/// and magnetometer slowly correct the drift this causes whenever the vehicle
+/// is not accelerating.
+#[derive(Debug, Default)]
+pub struct AttitudeEstimator {
+    orientation: Option<Quaternion>,
+    /// Latest specific force in mg
+    accelerometer: Option<Vector>,
+    /// Latest magnetic field in nT
+    magnetometer: Option<Vector>,
+    last_update: Option<VehicleTime>,
+}
+
+impl AttitudeEstimator {
+    pub const fn accelerometer(&mut self, raw: Vector3<i32>) {
+        self.accelerometer = Some(vector(raw));
+    }
+
+    pub const fn magnetometer(&mut self, raw: Vector3<i32>) {
+        self.magnetometer = Some(vector(raw));
+    }
+
+    /// Advance the orientation with an angular rate reading in mdps
+    pub fn gyroscope(&mut self, time: VehicleTime, raw: Vector3<i32>) {
+        let step = self
+            .last_update
+            .map(|last_update| (time.as_duration() - last_update.as_duration()).as_seconds_f64());
+        self.last_update = Some(time);
+
+        let (orientation, step) = match (self.orientation, step) {
+            (Some(orientation), Some(step)) if step > 0.0 && step < MAX_STEP => (orientation, step),
+            // Nothing to integrate from, so start from where gravity and north say the vehicle is
+            _ => {
+                self.orientation = self.initial_orientation();
+
+                return;
+            }
+        };
+
+        let [x, y, z] = vector(raw);
+        let mut rate = [
+            (x / 1000.0).to_radians(),
+            (y / 1000.0).to_radians(),
+            (z / 1000.0).to_radians(),
+        ];
+
+        if let Some(error) = self.correction(orientation) {
+            for (rate, error) in rate.iter_mut().zip(error) {
+                *rate += CORRECTION_GAIN * error;
+            }
+        }
+
+        let change = orientation * Quaternion::new(0.0, rate[0], rate[1], rate[2]);
+
+        self.orientation = Some((orientation + change * (step / 2.0)).normalize());
+    }
+
+    /// Current best estimate of the vehicle's orientation, if enough readings have arrived
+    pub const fn orientation(&self) -> Option<Quaternion> {
+        self.orientation
+    }
+
+    /// Orientation measured directly from gravity and the magnetic field
+    fn initial_orientation(&self) -> Option<Quaternion> {
+        let up = normalize(self.accelerometer?)?;
+        let west = normalize(cross(up, self.magnetometer?))?;
+        let north = cross(west, up);
+
+        Some(Quaternion::from_axes(north, west, up))
+    }
+
+    /// Rotation in the vehicle frame that would turn the estimated orientation
+    /// towards the measured one, or nothing if the measurements can't be trusted
+    fn correction(&self, orientation: Quaternion) -> Option<Vector> {
+        let accelerometer = self.accelerometer?;
+        let magnetometer = self.magnetometer?;
+
+        let [x, y, z] = accelerometer;
+        if ((x * x + y * y + z * z).sqrt() - 1000.0).abs() > ACCELERATION_TOLERANCE {
+            return None;
+        }
+
+        let measured_up = normalize(accelerometer)?;
+        let measured_field = normalize(magnetometer)?;
+
+        let estimated_up = orientation.conjugate().rotate([0.0, 0.0, 1.0]);
+
+        // The field's direction in the world according to the estimate, with
+        // its horizontal part swung round to north
+        let [field_x, field_y, field_z] = orientation.rotate(measured_field);
+        let estimated_field =
+            orientation
+                .conjugate()
+                .rotate([field_x.hypot(field_y), 0.0, field_z]);
+
+        let [up_x, up_y, up_z] = cross(measured_up, estimated_up);
+        let [field_x, field_y, field_z] = cross(measured_field, estimated_field);
+
+        Some([up_x + field_x, up_y + field_y, up_z + field_z])
+    }
+}
+
+const fn vector(raw: Vector3<i32>) -> Vector {
+    [raw.x as f64, raw.y as f64, raw.z as f64]
+}
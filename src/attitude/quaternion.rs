@@ -0,0 +1,141 @@
+use std::ops::{Add, Mul};
+
+/// A vector as `[x, y, z]`
+pub type Vector = [f64; 3];
+
+/// A rotation, taking vectors in the vehicle's frame to the world's frame
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quaternion {
+    pub w: f64,
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+/// An orientation as successive rotations about the world's Z axis, then the
+/// vehicle's Y axis, then the vehicle's X axis, all in radians
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EulerAngles {
+    pub roll: f64,
+    pub pitch: f64,
+    pub yaw: f64,
+}
+
+impl Quaternion {
+    pub const IDENTITY: Quaternion = Quaternion::new(1.0, 0.0, 0.0, 0.0);
+
+    pub const fn new(w: f64, x: f64, y: f64, z: f64) -> Self {
+        Self { w, x, y, z }
+    }
+
+    /// The rotation taking the vehicle's frame onto the given world axes,
+    /// each expressed in the vehicle's frame
+    pub fn from_axes(x: Vector, y: Vector, z: Vector) -> Self {
+        // Rows of the rotation matrix are the world axes
+        let [m00, m01, m02] = x;
+        let [m10, m11, m12] = y;
+        let [m20, m21, m22] = z;
+
+        let trace = m00 + m11 + m22;
+
+        let quaternion = if trace > 0.0 {
+            let s = (trace + 1.0).sqrt() * 2.0;
+
+            Quaternion::new(s / 4.0, (m21 - m12) / s, (m02 - m20) / s, (m10 - m01) / s)
+        } else if m00 > m11 && m00 > m22 {
+            let s = (1.0 + m00 - m11 - m22).sqrt() * 2.0;
+
+            Quaternion::new((m21 - m12) / s, s / 4.0, (m01 + m10) / s, (m02 + m20) / s)
+        } else if m11 > m22 {
+            let s = (1.0 + m11 - m00 - m22).sqrt() * 2.0;
+
+            Quaternion::new((m02 - m20) / s, (m01 + m10) / s, s / 4.0, (m12 + m21) / s)
+        } else {
+            let s = (1.0 + m22 - m00 - m11).sqrt() * 2.0;
+
+            Quaternion::new((m10 - m01) / s, (m02 + m20) / s, (m12 + m21) / s, s / 4.0)
+        };
+
+        quaternion.normalize()
+    }
+
+    pub fn conjugate(self) -> Self {
+        Quaternion::new(self.w, -self.x, -self.y, -self.z)
+    }
+
+    pub fn normalize(self) -> Self {
+        let norm = (self.w * self.w + self.x * self.x + self.y * self.y + self.z * self.z).sqrt();
+
+        if norm == 0.0 {
+            return Quaternion::IDENTITY;
+        }
+
+        Quaternion::new(self.w / norm, self.x / norm, self.y / norm, self.z / norm)
+    }
+
+    /// Rotate a vector from the vehicle's frame to the world's frame
+    pub fn rotate(self, [x, y, z]: Vector) -> Vector {
+        let rotated = self * Quaternion::new(0.0, x, y, z) * self.conjugate();
+
+        [rotated.x, rotated.y, rotated.z]
+    }
+
+    pub fn euler_angles(self) -> EulerAngles {
+        let Quaternion { w, x, y, z } = self;
+
+        EulerAngles {
+            roll: (2.0 * (w * x + y * z)).atan2(1.0 - 2.0 * (x * x + y * y)),
+            pitch: (2.0 * (w * y - z * x)).clamp(-1.0, 1.0).asin(),
+            yaw: (2.0 * (w * z + x * y)).atan2(1.0 - 2.0 * (y * y + z * z)),
+        }
+    }
+}
+
+impl Add for Quaternion {
+    type Output = Quaternion;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Quaternion::new(
+            self.w + rhs.w,
+            self.x + rhs.x,
+            self.y + rhs.y,
+            self.z + rhs.z,
+        )
+    }
+}
+
+impl Mul for Quaternion {
+    type Output = Quaternion;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Quaternion::new(
+            self.w * rhs.w - self.x * rhs.x - self.y * rhs.y - self.z * rhs.z,
+            self.w * rhs.x + self.x * rhs.w + self.y * rhs.z - self.z * rhs.y,
+            self.w * rhs.y - self.x * rhs.z + self.y * rhs.w + self.z * rhs.x,
+            self.w * rhs.z + self.x * rhs.y - self.y * rhs.x + self.z * rhs.w,
+        )
+    }
+}
+
+impl Mul<f64> for Quaternion {
+    type Output = Quaternion;
+
+    fn mul(self, rhs: f64) -> Self::Output {
+        Quaternion::new(self.w * rhs, self.x * rhs, self.y * rhs, self.z * rhs)
+    }
+}
+
+pub fn cross([ax, ay, az]: Vector, [bx, by, bz]: Vector) -> Vector {
+    [ay * bz - az * by, az * bx - ax * bz, ax * by - ay * bx]
+}
+
+/// The vector scaled to unit length, or nothing if it has no length to scale
+pub fn normalize([x, y, z]: Vector) -> Option<Vector> {
+    let norm = (x * x + y * y + z * z).sqrt();
+
+    if norm == 0.0 {
+        return None;
+    }
+
+    Some([x / norm, y / norm, z / norm])
+}
@@ -1 +1,461 @@
+use std::{
+    env,
+    error::Error,
+    fmt::{self, Display, Formatter},
+    hash::Hash,
+    io::{self, BufRead, ErrorKind},
+    net::{Ipv4Addr, SocketAddr},
+    thread,
+    time::{Duration, Instant},
+};
+
+use error_rate::ErrorRate;
+use flume::{Receiver, RecvTimeoutError, SendError, Sender};
+use iced::{futures::stream::BoxStream, Subscription};
+use iced_native::subscription::Recipe;
+use interlink::{
+    phy::{
+        self,
+        frame::{self, FrameError},
+        InterlinkMethod,
+    },
+    proto::{BarometerReading, PacketDown, PacketUp},
+};
+use net::{TcpTransport, UdpTransport};
+use sequence::SequenceTracker;
+use serial::SerialTransport;
+use stats::{LinkStats, StatsCollector};
+use tracing::{debug, error, trace, warn};
+
+use crate::util::env_or;
+
+/// Percentage of frames that may fail to decode when `LINK_ERROR_THRESHOLD` is not set.
+const DEFAULT_ERROR_THRESHOLD: u8 = 50;
+
+pub mod command;
+pub mod error_rate;
+pub mod handshake;
+pub mod net;
+pub mod sequence;
 pub mod serial;
+pub mod stats;
+pub mod time_sync;
+
+/// Event reported by the link to the vehicle, whatever transport it uses
+#[derive(Debug, Clone)]
+pub enum SerialEvent {
+    PacketReceived(PacketDown),
+    /// A packet queued on the [`Uplink`] could not be sent to the vehicle
+    SendFailed {
+        packet: PacketUp,
+        reason: String,
+    },
+    Connected {
+        method: InterlinkMethod,
+        /// Who is on the other end of the link, if known
+        peer: Option<String>,
+    },
+    /// Periodic report on the health of the link
+    Stats(LinkStats),
+    Disconnected,
+    /// A replay jumped to another point in the session, so nothing received
+    /// before the jump applies any more
+    Seeked {
+        /// Barometer readings from before liftoff, which altitudes are measured from
+        pad_readings: Vec<BarometerReading>,
+    },
+}
+
+/// State of the current link to the vehicle
+#[derive(Debug, Clone)]
+pub struct LinkStatus {
+    pub method: InterlinkMethod,
+    pub peer: Option<String>,
+    /// Not known until the transport first reports them
+    pub stats: Option<LinkStats>,
+    pub sequence: SequenceTracker,
+}
+
+/// A physical method of reaching the vehicle
+///
+/// Implementors only need to know how to find the vehicle and move packets
+/// over their medium, [`TransportSubscription`] takes care of reconnecting and
+/// reporting [`SerialEvent`]s to the application.
+pub trait Transport: Send + 'static {
+    type Connection: Connection;
+
+    /// The interlink method this transport communicates over
+    fn method(&self) -> InterlinkMethod;
+
+    /// Try to connect to the vehicle
+    ///
+    /// Returns `Ok(None)` if the vehicle could not be found, in which case
+    /// another attempt will be made after the refresh interval.
+    fn connect(&mut self) -> io::Result<Option<Self::Connection>>;
+}
+
+/// An open link to the vehicle
+pub trait Connection {
+    /// Human readable name of the other end of the link
+    fn peer(&self) -> String;
+
+    fn send(&mut self, packet: &PacketUp) -> io::Result<()>;
+
+    /// Wait a short while for the next packet from the vehicle
+    ///
+    /// Returns `Ok(None)` if no packet arrived in time, leaving any partially
+    /// received packet to be completed by the next call.
+    fn receive(&mut self) -> Result<Option<PacketDown>, ReceiveError>;
+
+    /// Total number of bytes received over the link, including any corrupt frames
+    fn bytes_received(&self) -> u64;
+
+    /// Gracefully shut down the link before it is dropped
+    fn close(&mut self) {}
+}
+
+#[derive(Debug)]
+pub enum ReceiveError {
+    Io(io::Error),
+    Decode(FrameError),
+}
+
+impl Display for ReceiveError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ReceiveError::Io(error) => write!(f, "failed to communicate with vehicle: {}", error),
+            ReceiveError::Decode(error) => write!(f, "failed to deserialize packet: {}", error),
+        }
+    }
+}
+
+impl Error for ReceiveError {}
+
+impl From<io::Error> for ReceiveError {
+    fn from(error: io::Error) -> Self {
+        ReceiveError::Io(error)
+    }
+}
+
+impl From<FrameError> for ReceiveError {
+    fn from(error: FrameError) -> Self {
+        ReceiveError::Decode(error)
+    }
+}
+
+/// Serialize a packet into a CRC protected COBS frame
+fn encode_frame(packet: &PacketUp) -> io::Result<Vec<u8>> {
+    let mut buffer = [0; phy::serial::BUFFER_SIZE];
+
+    frame::encode(packet, &mut buffer)
+        .map(|frame| frame.to_vec())
+        .map_err(|error| io::Error::new(ErrorKind::InvalidData, error.to_string()))
+}
+
+/// Read the next frame from a stream of COBS frames
+///
+/// A timed out read leaves the partial frame in `data_storage` to be completed
+/// by the next call. Every byte read is added to `bytes_received`.
+fn read_frame(
+    reader: &mut impl BufRead,
+    data_storage: &mut Vec<u8>,
+    bytes_received: &mut u64,
+) -> Result<Option<PacketDown>, ReceiveError> {
+    let previous_length = data_storage.len();
+    let read = reader.read_until(phy::serial::COBS_SENTINEL, data_storage);
+
+    // Count partial reads that timed out as well
+    *bytes_received += (data_storage.len() - previous_length) as u64;
+
+    match read {
+        Ok(0) => {
+            return Err(ReceiveError::Io(io::Error::new(
+                ErrorKind::UnexpectedEof,
+                "connection closed",
+            )))
+        }
+        Ok(_) => {}
+        // Suppress time outs
+        Err(error) if matches!(error.kind(), ErrorKind::TimedOut | ErrorKind::WouldBlock) => {
+            return Ok(None)
+        }
+        Err(error) => return Err(error.into()),
+    }
+
+    if data_storage.len() > phy::serial::BUFFER_SIZE {
+        trace!(
+            "Received {} bytes more than expected",
+            data_storage.len() - phy::serial::BUFFER_SIZE
+        );
+    }
+
+    let packet = frame::decode::<PacketDown>(data_storage);
+
+    data_storage.clear();
+
+    Ok(Some(packet?))
+}
+
+/// Start the transport selected by the `INTERLINK` environment variable
+///
+/// `INTERLINK` may be `serial`, the default, `udp` or `tcp`. The network
+/// transports listen on `INTERLINK_ADDRESS`, or on all interfaces at
+/// [`phy::net::DEFAULT_PORT`] if it is not set.
+pub fn start_transport(refresh_interval: Duration) -> TransportSubscription {
+    let method = env::var("INTERLINK").unwrap_or_default();
+
+    let address = env::var("INTERLINK_ADDRESS")
+        .ok()
+        .and_then(|address| match address.parse::<SocketAddr>() {
+            Ok(address) => Some(address),
+            Err(error) => {
+                error!(%error, %address, "Invalid INTERLINK_ADDRESS, using the default");
+
+                None
+            }
+        })
+        .unwrap_or_else(|| SocketAddr::from((Ipv4Addr::UNSPECIFIED, phy::net::DEFAULT_PORT)));
+
+    match method.to_ascii_lowercase().as_str() {
+        "udp" => TransportSubscription::start(UdpTransport::new(address), refresh_interval),
+        "tcp" => TransportSubscription::start(TcpTransport::new(address), refresh_interval),
+        "" | "serial" => TransportSubscription::start(SerialTransport, refresh_interval),
+        _ => {
+            error!(%method, "Unknown INTERLINK, expected serial, udp or tcp. Using serial");
+
+            TransportSubscription::start(SerialTransport, refresh_interval)
+        }
+    }
+}
+
+/// Handle for sending packets up to the vehicle
+#[derive(Debug, Clone)]
+pub struct Uplink {
+    sender: Sender<PacketUp>,
+}
+
+impl Uplink {
+    /// Queue a packet to be sent to the connected vehicle
+    ///
+    /// Packets that cannot be sent, including any queued while no vehicle is
+    /// connected, are reported back as [`SerialEvent::SendFailed`].
+    pub fn send(&self, packet: PacketUp) {
+        if self.sender.send(packet).is_err() {
+            error!("Uplink channel closed, the transport thread has shut down");
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TransportSubscription {
+    method: InterlinkMethod,
+    receiver: Receiver<SerialEvent>,
+    uplink: Uplink,
+}
+
+impl TransportSubscription {
+    pub fn start<T: Transport>(transport: T, refresh_interval: Duration) -> Self {
+        let (sender, receiver) = flume::unbounded();
+        let (uplink_sender, uplink_receiver) = flume::unbounded();
+        let method = transport.method();
+
+        thread::spawn(move || {
+            transport_listener(transport, sender, uplink_receiver, refresh_interval)
+        });
+
+        Self {
+            method,
+            receiver,
+            uplink: Uplink {
+                sender: uplink_sender,
+            },
+        }
+    }
+
+    pub fn subscription(&self) -> Subscription<SerialEvent> {
+        Subscription::from_recipe(self.clone())
+    }
+
+    pub const fn uplink(&self) -> &Uplink {
+        &self.uplink
+    }
+}
+
+impl<Hasher, Event> Recipe<Hasher, Event> for TransportSubscription
+where
+    Hasher: std::hash::Hasher,
+{
+    type Output = SerialEvent;
+
+    fn hash(&self, state: &mut Hasher) {
+        // Only one of these should ever exist per method due to its reconnecting nature
+        self.method.hash(state);
+    }
+
+    fn stream(self: Box<Self>, _input: BoxStream<Event>) -> BoxStream<Self::Output> {
+        Box::pin(self.receiver.into_stream())
+    }
+}
+
+fn transport_listener<T: Transport>(
+    mut transport: T,
+    sender: Sender<SerialEvent>,
+    uplink: Receiver<PacketUp>,
+    refresh_interval: Duration,
+) {
+    let method = transport.method();
+    let error_threshold = env_or("LINK_ERROR_THRESHOLD", DEFAULT_ERROR_THRESHOLD);
+
+    trace!(?method, "Transport subscription spawned");
+
+    let mut first_retry = true;
+
+    loop {
+        if first_retry {
+            debug!(?method, "Searching for vehicle");
+        }
+
+        match transport.connect() {
+            Ok(Some(mut connection)) => {
+                let peer = connection.peer();
+
+                debug!(?method, %peer, "Connected to vehicle");
+
+                if sender
+                    .send(SerialEvent::Connected {
+                        method,
+                        peer: Some(peer),
+                    })
+                    .is_err()
+                {
+                    trace!("SerialEvent channel closed, shutting down thread");
+
+                    return;
+                }
+
+                let error_rate = ErrorRate::new(error_threshold);
+
+                if run_connection(&mut connection, &sender, &uplink, error_rate).is_err() {
+                    trace!("SerialEvent channel closed, shutting down thread");
+                    connection.close();
+
+                    return;
+                }
+
+                connection.close();
+
+                if sender.send(SerialEvent::Disconnected).is_err() {
+                    return;
+                }
+
+                trace!(?method, "Closed connection");
+
+                first_retry = true;
+            }
+            Ok(None) => {
+                if first_retry {
+                    warn!(?method, "No vehicle found");
+                }
+                first_retry = false;
+            }
+            Err(error) => {
+                error!(%error, ?method, "Unable to connect to vehicle");
+            }
+        }
+
+        // Reject anything queued while waiting, rather than sending stale packets later
+        let deadline = Instant::now() + refresh_interval;
+
+        loop {
+            match uplink.recv_deadline(deadline) {
+                Ok(packet) => {
+                    let failed = SerialEvent::SendFailed {
+                        packet,
+                        reason: String::from("no vehicle connected"),
+                    };
+
+                    if sender.send(failed).is_err() {
+                        return;
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => {
+                    trace!("Uplink channel closed, shutting down thread");
+
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// Pass packets from an open connection on to the application until it fails
+///
+/// Frames that fail to decode are skipped, and only end the connection once
+/// the `error_rate` goes over its threshold.
+///
+/// Returns `Err` if the application has closed the channel and the listener
+/// should shut down.
+fn run_connection<C: Connection>(
+    connection: &mut C,
+    sender: &Sender<SerialEvent>,
+    uplink: &Receiver<PacketUp>,
+    mut error_rate: ErrorRate,
+) -> Result<(), SendError<SerialEvent>> {
+    let mut stats = StatsCollector::default();
+
+    loop {
+        for packet in uplink.try_iter() {
+            match connection.send(&packet) {
+                Ok(()) => trace!(?packet, "Sent packet"),
+                Err(error) => {
+                    warn!(%error, ?packet, "Failed to send packet");
+
+                    sender.send(SerialEvent::SendFailed {
+                        packet,
+                        reason: error.to_string(),
+                    })?;
+                }
+            }
+        }
+
+        match connection.receive() {
+            Ok(Some(packet)) => {
+                error_rate.success();
+                stats.packet_received();
+
+                sender.send(SerialEvent::PacketReceived(packet))?;
+            }
+            Ok(None) => {}
+            Err(ReceiveError::Decode(error)) => {
+                stats.frame_error(&error);
+
+                // The bad frame has been discarded, so carry on from the next one
+                if error_rate.failure() {
+                    error!(
+                        %error,
+                        error_rate = error_rate.percentage(),
+                        "Too many corrupt frames, dropping connection to vehicle"
+                    );
+
+                    return Ok(());
+                }
+
+                warn!(
+                    %error,
+                    error_rate = error_rate.percentage(),
+                    "Skipping corrupt frame"
+                );
+            }
+            Err(error) => {
+                error!(%error, "Lost connection to vehicle");
+
+                return Ok(());
+            }
+        }
+
+        if let Some(stats) = stats.poll(connection.bytes_received(), error_rate.percentage()) {
+            sender.send(SerialEvent::Stats(stats))?;
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use std::collections::VecDeque;
+
+use interlink::proto::{Command, CommandId, NakReason, PacketDownData, PacketUp};
+use time::Duration;
+use tracing::{error, info, warn};
+
+use crate::{time_manager::unit::LocalTime, util::env_or};
+
+/// How many milliseconds to wait for a response when `COMMAND_TIMEOUT_MS` is not set.
+const DEFAULT_TIMEOUT_MS: u32 = 1000;
+/// How many times to resend a command when `COMMAND_RETRIES` is not set.
+const DEFAULT_RETRIES: u8 = 3;
+/// Number of commands kept in the history.
+const HISTORY_LENGTH: usize = 32;
+
+#[derive(Debug, Clone)]
+pub enum CommandStatus {
+    /// Waiting for the vehicle to respond
+    Pending,
+    /// The vehicle carried out the command
+    Acknowledged { round_trip: Duration },
+    /// The vehicle refused the command
+    Refused {
+        reason: NakReason,
+        round_trip: Duration,
+    },
+    /// The vehicle never responded
+    TimedOut,
+    /// The command could not be sent
+    Failed { reason: String },
+}
+
+/// A command sent to the vehicle and what became of it
+#[derive(Debug, Clone)]
+pub struct TrackedCommand {
+    pub id: CommandId,
+    pub command: Command,
+    pub status: CommandStatus,
+    /// How many times the command has been sent
+    pub attempts: u8,
+    last_attempt: LocalTime,
+}
+
+impl TrackedCommand {
+    const fn packet(&self) -> PacketUp {
+        PacketUp::Command {
+            id: self.id,
+            command: self.command,
+        }
+    }
+}
+
+/// Matches commands sent to the vehicle with their responses, resending any
+/// that go unanswered and can safely be repeated
+#[derive(Debug)]
+pub struct CommandTracker {
+    next_id: CommandId,
+    /// Most recent command first
+    history: VecDeque<TrackedCommand>,
+
+    timeout: Duration,
+    retries: u8,
+}
+
+impl CommandTracker {
+    /// Create a tracker configured by the `COMMAND_TIMEOUT_MS` and
+    /// `COMMAND_RETRIES` environment variables
+    pub fn from_env() -> Self {
+        Self {
+            next_id: 0,
+            history: VecDeque::with_capacity(HISTORY_LENGTH),
+            timeout: Duration::milliseconds(
+                env_or("COMMAND_TIMEOUT_MS", DEFAULT_TIMEOUT_MS).into(),
+            ),
+            retries: env_or("COMMAND_RETRIES", DEFAULT_RETRIES),
+        }
+    }
+
+    /// Start tracking a new command, returning the packet to send
+    pub fn send(&mut self, command: Command, now: LocalTime) -> PacketUp {
+        let id = self.next_id;
+        self.next_id = self.next_id.wrapping_add(1);
+
+        info!(id, ?command, "Sending command");
+
+        if self.history.len() == HISTORY_LENGTH {
+            self.history.pop_back();
+        }
+
+        self.history.push_front(TrackedCommand {
+            id,
+            command,
+            status: CommandStatus::Pending,
+            attempts: 1,
+            last_attempt: now,
+        });
+
+        PacketUp::Command { id, command }
+    }
+
+    /// Time out unanswered commands, returning the packets to resend
+    pub fn poll(&mut self, now: LocalTime) -> Vec<PacketUp> {
+        let timeout = self.timeout;
+        let retries = self.retries;
+
+        self.history
+            .iter_mut()
+            .filter(|tracked| matches!(tracked.status, CommandStatus::Pending))
+            .filter(|tracked| now.duration_since(&tracked.last_attempt) >= timeout)
+            .filter_map(|tracked| {
+                if !is_repeatable(tracked.command) {
+                    error!(
+                        id = tracked.id,
+                        command = ?tracked.command,
+                        "Command timed out, not resending as it may have been carried out"
+                    );
+                    tracked.status = CommandStatus::TimedOut;
+
+                    return None;
+                }
+
+                if tracked.attempts > retries {
+                    warn!(id = tracked.id, command = ?tracked.command, "Command timed out");
+                    tracked.status = CommandStatus::TimedOut;
+
+                    return None;
+                }
+
+                warn!(
+                    id = tracked.id,
+                    attempt = tracked.attempts,
+                    "No response to command, retrying"
+                );
+
+                tracked.attempts += 1;
+                tracked.last_attempt = now;
+
+                Some(tracked.packet())
+            })
+            .collect()
+    }
+
+    /// Match a packet from the vehicle to the command it responds to
+    pub fn response_received(&mut self, data: &PacketDownData, now: LocalTime) {
+        let (id, response) = match *data {
+            PacketDownData::Ack(id) | PacketDownData::Pong { id, .. } => (id, None),
+            PacketDownData::Nak { id, reason } => (id, Some(reason)),
+            _ => return,
+        };
+
+        let tracked = match self.pending(id) {
+            Some(tracked) => tracked,
+            None => {
+                // Most likely the answer to an attempt that was already retried
+                warn!(id, "Response to unknown or settled command");
+
+                return;
+            }
+        };
+
+        let round_trip = now.duration_since(&tracked.last_attempt);
+
+        tracked.status = match response {
+            None => {
+                info!(id, command = ?tracked.command, %round_trip, "Command acknowledged");
+
+                CommandStatus::Acknowledged { round_trip }
+            }
+            Some(reason) => {
+                warn!(id, command = ?tracked.command, %reason, "Command refused");
+
+                CommandStatus::Refused { reason, round_trip }
+            }
+        };
+    }
+
+    /// Fail a command whose packet the transport was unable to send
+    pub fn send_failed(&mut self, packet: &PacketUp, reason: &str) {
+        if let PacketUp::Command { id, .. } = *packet {
+            if let Some(tracked) = self.pending(id) {
+                error!(id, command = ?tracked.command, %reason, "Unable to send command");
+
+                tracked.status = CommandStatus::Failed {
+                    reason: reason.to_string(),
+                };
+            }
+        }
+    }
+
+    pub fn history(&self) -> impl Iterator<Item = &TrackedCommand> {
+        self.history.iter()
+    }
+
+    fn pending(&mut self, id: CommandId) -> Option<&mut TrackedCommand> {
+        self.history
+            .iter_mut()
+            .find(|tracked| tracked.id == id && matches!(tracked.status, CommandStatus::Pending))
+    }
+}
+
+/// Whether a command can safely be carried out twice, as it is resent if the
+/// vehicle's answer is lost
+///
+/// A vehicle answers a reboot before restarting, so resending one would only
+/// reboot it again.
+pub const fn is_repeatable(command: Command) -> bool {
+    !matches!(command, Command::FirePyro { .. } | Command::Reboot)
+}
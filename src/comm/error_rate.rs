@@ -0,0 +1,63 @@
+use std::collections::VecDeque;
+
+/// Number of recent frames the error rate is measured over
+const WINDOW: usize = 64;
+
+/// Fraction of recent frames that failed to decode
+///
+/// A noisy link will corrupt the occasional frame, which can be skipped
+/// without losing the connection. A link where most frames are corrupt is
+/// more likely talking to the wrong device or at the wrong baud rate.
+#[derive(Debug)]
+pub struct ErrorRate {
+    /// Whether each recent frame failed, most recent last
+    frames: VecDeque<bool>,
+    errors: usize,
+    /// Percentage of failed frames above which the link is considered broken
+    threshold: u8,
+}
+
+impl ErrorRate {
+    pub fn new(threshold: u8) -> Self {
+        Self {
+            frames: VecDeque::with_capacity(WINDOW),
+            errors: 0,
+            threshold: threshold.min(100),
+        }
+    }
+
+    /// Record a frame that decoded successfully
+    pub fn success(&mut self) {
+        self.record(false);
+    }
+
+    /// Record a frame that failed to decode, returning whether the error rate
+    /// is now over the threshold
+    pub fn failure(&mut self) -> bool {
+        self.record(true);
+
+        // Give the link a full window before judging it
+        self.frames.len() == WINDOW && self.percentage() > self.threshold
+    }
+
+    /// Percentage of recent frames that failed to decode
+    pub fn percentage(&self) -> u8 {
+        if self.frames.is_empty() {
+            return 0;
+        }
+
+        (self.errors * 100 / self.frames.len()) as u8
+    }
+
+    fn record(&mut self, failed: bool) {
+        if self.frames.len() == WINDOW && self.frames.pop_front() == Some(true) {
+            self.errors -= 1;
+        }
+
+        self.frames.push_back(failed);
+
+        if failed {
+            self.errors += 1;
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use interlink::proto::{Capabilities, Protocol, PROTOCOL_VERSION};
+use tracing::{error, info, warn};
+
+/// Protocol spoken by this ground station, sent in the welcome
+pub const STATION_PROTOCOL: Protocol = Protocol::current(Capabilities::ALL);
+
+/// How well the ground station and a vehicle understand each other
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compatibility {
+    /// Both speak the same protocol with the same capabilities
+    Full,
+    /// Both speak the same protocol, but the vehicle lacks some of its
+    /// optional parts, which are disabled
+    Degraded { missing: Capabilities },
+    /// Packets may not decode the same way at both ends, so the vehicle is
+    /// never commanded and nothing but its hello is taken in
+    VersionMismatch { vehicle: u16 },
+}
+
+impl Compatibility {
+    /// Compare the protocol a vehicle announced in its hello against the station's own
+    pub fn negotiate(vehicle: Protocol) -> Self {
+        if vehicle.version != PROTOCOL_VERSION {
+            error!(
+                station = PROTOCOL_VERSION,
+                vehicle = vehicle.version,
+                "Vehicle speaks a different protocol version"
+            );
+
+            return Compatibility::VersionMismatch {
+                vehicle: vehicle.version,
+            };
+        }
+
+        let missing = STATION_PROTOCOL
+            .capabilities
+            .difference(vehicle.capabilities);
+
+        if missing.is_empty() {
+            info!(
+                version = vehicle.version,
+                "Vehicle speaks the same protocol"
+            );
+
+            Compatibility::Full
+        } else {
+            warn!(%missing, "Vehicle does not support the full protocol");
+
+            Compatibility::Degraded { missing }
+        }
+    }
+
+    /// Whether the station may use an optional part of the protocol with the vehicle
+    pub const fn supports(self, capability: Capabilities) -> bool {
+        match self {
+            Compatibility::Full => true,
+            Compatibility::Degraded { missing } => !missing.contains(capability),
+            Compatibility::VersionMismatch { .. } => false,
+        }
+    }
+}
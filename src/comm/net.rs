@@ -0,0 +1,231 @@
+use std::{
+    io::{self, BufReader, ErrorKind, Write},
+    net::{SocketAddr, TcpListener, TcpStream, UdpSocket},
+    time::{Duration, Instant},
+};
+
+use interlink::{
+    phy::{self, frame, InterlinkMethod},
+    proto::{PacketDown, PacketUp},
+};
+use tracing::{info, trace};
+
+use super::{encode_frame, read_frame, Connection, ReceiveError, Transport};
+
+/// How long to wait for a packet before giving up on a read
+const READ_TIMEOUT: Duration = Duration::from_millis(10);
+
+/// Largest payload a UDP datagram can carry
+const MAX_DATAGRAM_SIZE: usize = 65_507;
+
+/// How long a UDP relay may stay silent before it is considered disconnected
+const UDP_LINK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Listens for a relay streaming frames over TCP
+#[derive(Debug)]
+pub struct TcpTransport {
+    address: SocketAddr,
+    listener: Option<TcpListener>,
+}
+
+impl TcpTransport {
+    pub const fn new(address: SocketAddr) -> Self {
+        Self {
+            address,
+            listener: None,
+        }
+    }
+}
+
+impl Transport for TcpTransport {
+    type Connection = TcpConnection;
+
+    fn method(&self) -> InterlinkMethod {
+        InterlinkMethod::Tcp
+    }
+
+    fn connect(&mut self) -> io::Result<Option<Self::Connection>> {
+        let listener = match &mut self.listener {
+            Some(listener) => listener,
+            None => {
+                let listener = TcpListener::bind(self.address)?;
+                listener.set_nonblocking(true)?;
+
+                info!("Listening for TCP relay on {}", listener.local_addr()?);
+
+                self.listener.insert(listener)
+            }
+        };
+
+        let (stream, peer) = match listener.accept() {
+            Ok(connection) => connection,
+            Err(error) if error.kind() == ErrorKind::WouldBlock => return Ok(None),
+            Err(error) => return Err(error),
+        };
+
+        stream.set_nonblocking(false)?;
+        stream.set_read_timeout(Some(READ_TIMEOUT))?;
+        stream.set_nodelay(true)?;
+
+        Ok(Some(TcpConnection {
+            stream: BufReader::new(stream),
+            peer,
+            data_storage: Vec::with_capacity(phy::serial::BUFFER_SIZE),
+            bytes_received: 0,
+        }))
+    }
+}
+
+pub struct TcpConnection {
+    stream: BufReader<TcpStream>,
+    peer: SocketAddr,
+    data_storage: Vec<u8>,
+    bytes_received: u64,
+}
+
+impl Connection for TcpConnection {
+    fn peer(&self) -> String {
+        self.peer.to_string()
+    }
+
+    fn send(&mut self, packet: &PacketUp) -> io::Result<()> {
+        self.stream.get_mut().write_all(&encode_frame(packet)?)
+    }
+
+    fn receive(&mut self) -> Result<Option<PacketDown>, ReceiveError> {
+        read_frame(
+            &mut self.stream,
+            &mut self.data_storage,
+            &mut self.bytes_received,
+        )
+    }
+
+    fn bytes_received(&self) -> u64 {
+        self.bytes_received
+    }
+}
+
+/// Listens for a relay sending frames in UDP datagrams
+///
+/// The first host to send a datagram becomes the peer, and packets sent up to
+/// the vehicle are addressed to it.
+#[derive(Debug)]
+pub struct UdpTransport {
+    address: SocketAddr,
+    socket: Option<UdpSocket>,
+}
+
+impl UdpTransport {
+    pub const fn new(address: SocketAddr) -> Self {
+        Self {
+            address,
+            socket: None,
+        }
+    }
+}
+
+impl Transport for UdpTransport {
+    type Connection = UdpConnection;
+
+    fn method(&self) -> InterlinkMethod {
+        InterlinkMethod::Udp
+    }
+
+    fn connect(&mut self) -> io::Result<Option<Self::Connection>> {
+        let socket = match &mut self.socket {
+            Some(socket) => socket,
+            None => {
+                let socket = UdpSocket::bind(self.address)?;
+                socket.set_read_timeout(Some(READ_TIMEOUT))?;
+
+                info!("Listening for UDP relay on {}", socket.local_addr()?);
+
+                self.socket.insert(socket)
+            }
+        };
+
+        let mut datagram = vec![0; MAX_DATAGRAM_SIZE];
+
+        let (length, peer) = match socket.recv_from(&mut datagram) {
+            Ok(received) => received,
+            Err(error) if matches!(error.kind(), ErrorKind::TimedOut | ErrorKind::WouldBlock) => {
+                return Ok(None)
+            }
+            Err(error) => return Err(error),
+        };
+
+        Ok(Some(UdpConnection {
+            socket: socket.try_clone()?,
+            peer,
+            pending: datagram[..length].to_vec(),
+            datagram,
+            last_received: Instant::now(),
+            bytes_received: length as u64,
+        }))
+    }
+}
+
+pub struct UdpConnection {
+    socket: UdpSocket,
+    peer: SocketAddr,
+    /// Received bytes not yet split into frames
+    pending: Vec<u8>,
+    datagram: Vec<u8>,
+    last_received: Instant,
+    bytes_received: u64,
+}
+
+impl Connection for UdpConnection {
+    fn peer(&self) -> String {
+        self.peer.to_string()
+    }
+
+    fn send(&mut self, packet: &PacketUp) -> io::Result<()> {
+        self.socket.send_to(&encode_frame(packet)?, self.peer)?;
+
+        Ok(())
+    }
+
+    fn receive(&mut self) -> Result<Option<PacketDown>, ReceiveError> {
+        if let Some(end) = self
+            .pending
+            .iter()
+            .position(|&byte| byte == phy::serial::COBS_SENTINEL)
+        {
+            let mut frame = self.pending.drain(..=end).collect::<Vec<_>>();
+
+            return Ok(Some(frame::decode::<PacketDown>(&mut frame)?));
+        }
+
+        match self.socket.recv_from(&mut self.datagram) {
+            Ok((length, peer)) if peer == self.peer => {
+                self.pending.extend_from_slice(&self.datagram[..length]);
+                self.last_received = Instant::now();
+                self.bytes_received += length as u64;
+
+                if self.pending.len() > phy::serial::BUFFER_SIZE {
+                    trace!(
+                        "Received {} bytes more than expected",
+                        self.pending.len() - phy::serial::BUFFER_SIZE
+                    );
+                }
+            }
+            Ok((_, peer)) => trace!(%peer, "Ignoring datagram from unknown peer"),
+            Err(error) if matches!(error.kind(), ErrorKind::TimedOut | ErrorKind::WouldBlock) => {
+                if self.last_received.elapsed() > UDP_LINK_TIMEOUT {
+                    return Err(ReceiveError::Io(io::Error::new(
+                        ErrorKind::TimedOut,
+                        "relay stopped sending datagrams",
+                    )));
+                }
+            }
+            Err(error) => return Err(error.into()),
+        }
+
+        Ok(None)
+    }
+
+    fn bytes_received(&self) -> u64 {
+        self.bytes_received
+    }
+}
@@ -0,0 +1,156 @@
+use interlink::proto::SequenceNumber;
+
+/// How far back out of order packets are remembered
+const WINDOW: u16 = 64;
+
+/// How a packet's sequence number relates to the packets before it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arrival {
+    /// The packet directly follows the last one
+    InOrder,
+    /// The given number of packets were skipped before this one
+    AfterLoss(u16),
+    /// The packet arrived after later packets, filling in a gap
+    OutOfOrder,
+    /// The packet was already received
+    Duplicate,
+}
+
+/// Tracks the sequence numbers of packets from the vehicle to find those
+/// lost, duplicated or reordered in transit
+#[derive(Debug, Clone, Default)]
+pub struct SequenceTracker {
+    /// Highest sequence number received so far
+    highest: Option<SequenceNumber>,
+    /// Packets received in the window below `highest`, where bit `n` is set if
+    /// `highest - n` has been received
+    received_window: u64,
+
+    pub received: u64,
+    pub lost: u64,
+    pub duplicates: u64,
+    pub out_of_order: u64,
+}
+
+impl SequenceTracker {
+    pub fn packet_received(&mut self, sequence: SequenceNumber) -> Arrival {
+        let highest = match self.highest {
+            Some(highest) => highest,
+            None => return self.restart(sequence),
+        };
+
+        let ahead = sequence.wrapping_sub(highest);
+        let behind = highest.wrapping_sub(sequence);
+
+        if ahead == 0 {
+            self.duplicates += 1;
+
+            Arrival::Duplicate
+        } else if ahead <= SequenceNumber::MAX / 2 {
+            let skipped = ahead - 1;
+
+            self.highest = Some(sequence);
+            self.received_window = self.received_window.checked_shl(ahead.into()).unwrap_or(0) | 1;
+            self.received += 1;
+            self.lost += u64::from(skipped);
+
+            if skipped == 0 {
+                Arrival::InOrder
+            } else {
+                Arrival::AfterLoss(skipped)
+            }
+        } else if behind < WINDOW {
+            let bit = 1 << behind;
+
+            if self.received_window & bit != 0 {
+                self.duplicates += 1;
+
+                return Arrival::Duplicate;
+            }
+
+            // Counted as lost when the packets after it arrived
+            self.received_window |= bit;
+            self.received += 1;
+            self.lost = self.lost.saturating_sub(1);
+            self.out_of_order += 1;
+
+            Arrival::OutOfOrder
+        } else {
+            // Too far back to be reordered, so the vehicle most likely rebooted
+            self.restart(sequence)
+        }
+    }
+
+    /// Percentage of packets sent by the vehicle that never arrived
+    pub fn loss_percentage(&self) -> f32 {
+        let sent = self.received + self.lost;
+
+        if sent == 0 {
+            return 0.0;
+        }
+
+        self.lost as f32 / sent as f32 * 100.0
+    }
+
+    /// Take `sequence` as the start of a new stream of packets, keeping the totals
+    pub const fn restart(&mut self, sequence: SequenceNumber) -> Arrival {
+        self.highest = Some(sequence);
+        // Nothing from before the first packet was counted as lost, so treat it as already received
+        self.received_window = u64::MAX;
+        self.received += 1;
+
+        Arrival::InOrder
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Arrival, SequenceTracker};
+
+    #[test]
+    fn wraps_around() {
+        let mut tracker = SequenceTracker::default();
+
+        assert_eq!(tracker.packet_received(65534), Arrival::InOrder);
+        assert_eq!(tracker.packet_received(65535), Arrival::InOrder);
+        assert_eq!(tracker.packet_received(0), Arrival::InOrder);
+        assert_eq!(tracker.packet_received(2), Arrival::AfterLoss(1));
+        assert_eq!(tracker.packet_received(1), Arrival::OutOfOrder);
+        assert_eq!(tracker.packet_received(65535), Arrival::Duplicate);
+
+        assert_eq!(tracker.lost, 0);
+        assert_eq!(tracker.duplicates, 1);
+    }
+
+    #[test]
+    fn restart_after_high_sequence() {
+        let mut tracker = SequenceTracker::default();
+
+        tracker.packet_received(40000);
+        tracker.packet_received(40001);
+
+        // The hello after a reboot
+        assert_eq!(tracker.restart(0), Arrival::InOrder);
+        assert_eq!(tracker.packet_received(1), Arrival::InOrder);
+
+        assert_eq!(tracker.lost, 0);
+        assert_eq!(tracker.received, 4);
+    }
+
+    #[test]
+    fn restart_after_low_sequence() {
+        let mut tracker = SequenceTracker::default();
+
+        for sequence in 0..10 {
+            tracker.packet_received(sequence);
+        }
+
+        // Would otherwise be taken for duplicates of the packets before the reboot
+        assert_eq!(tracker.restart(0), Arrival::InOrder);
+        assert_eq!(tracker.packet_received(1), Arrival::InOrder);
+        assert_eq!(tracker.packet_received(2), Arrival::InOrder);
+
+        assert_eq!(tracker.duplicates, 0);
+        assert_eq!(tracker.lost, 0);
+    }
+}
@@ -1,181 +1,104 @@
 use std::{
-    hash::Hash,
-    io::{BufRead, BufReader, ErrorKind},
-    thread,
+    env,
+    io::{self, BufReader, ErrorKind, Write},
+    path::Path,
     time::Duration,
 };
 
-use flume::{Receiver, Sender};
-use iced::{futures::stream::BoxStream, Subscription};
-use iced_native::subscription::Recipe;
 use interlink::{
-    phy,
+    phy::{self, InterlinkMethod},
     proto::{PacketDown, PacketUp},
 };
-use serialport::SerialPortType;
-use tracing::{debug, error, trace, warn};
+use serialport::{SerialPort, SerialPortType};
+use tracing::{trace, warn};
 
-#[derive(Debug, Clone)]
-pub struct SerialSubscription {
-    receiver: Receiver<SerialEvent>,
-}
-
-#[derive(Debug, Clone)]
-pub enum SerialEvent {
-    PacketReceived(PacketDown),
-    Connected,
-    Disconnected,
-}
+use super::{encode_frame, read_frame, Connection, ReceiveError, Transport};
 
-impl SerialSubscription {
-    pub fn start(refresh_interval: Duration) -> Self {
-        let (sender, receiver) = flume::unbounded();
+/// Connects to the vehicle over USB serial
+#[derive(Debug, Default)]
+pub struct SerialTransport;
 
-        thread::spawn(move || serial_listener(sender, refresh_interval));
+impl Transport for SerialTransport {
+    type Connection = SerialConnection;
 
-        Self { receiver }
+    fn method(&self) -> InterlinkMethod {
+        InterlinkMethod::Serial
     }
 
-    pub fn subscription(&self) -> Subscription<SerialEvent> {
-        Subscription::from_recipe(self.clone())
+    fn connect(&mut self) -> io::Result<Option<Self::Connection>> {
+        let port = match try_find_serial_port() {
+            Some(port) => port,
+            None => return Ok(None),
+        };
+
+        trace!("Connecting to board");
+        let mut port = serialport::new(port, 0)
+            .flow_control(serialport::FlowControl::Hardware)
+            .timeout(Duration::from_millis(10))
+            .open()?;
+
+        port.write_data_terminal_ready(true).ok();
+
+        Ok(Some(SerialConnection {
+            port: BufReader::with_capacity(9, port),
+            data_storage: Vec::with_capacity(phy::serial::BUFFER_SIZE),
+            bytes_received: 0,
+        }))
     }
 }
 
-impl<Hasher, Event> Recipe<Hasher, Event> for SerialSubscription
-where
-    Hasher: std::hash::Hasher,
-{
-    type Output = SerialEvent;
-
-    fn hash(&self, state: &mut Hasher) {
-        // Only one of these should ever exist due to its reconnecting nature
-        (phy::serial::VID, phy::serial::PID).hash(state);
-    }
-
-    fn stream(self: Box<Self>, _input: BoxStream<Event>) -> BoxStream<Self::Output> {
-        Box::pin(self.receiver.into_stream())
-    }
+pub struct SerialConnection {
+    port: BufReader<Box<dyn SerialPort>>,
+    data_storage: Vec<u8>,
+    bytes_received: u64,
 }
 
-pub fn serial_listener(sender: Sender<SerialEvent>, refresh_interval: Duration) {
-    trace!("Serial subscription spawned");
-
-    let mut first_retry = true;
-
-    loop {
-        if first_retry {
-            debug!("Searching for board");
-        }
-
-        let port = try_find_serial_port();
-
-        if let Some(port) = port {
-            trace!("Connecting to board");
-            let mut port = match serialport::new(port, 0)
-                .flow_control(serialport::FlowControl::Hardware)
-                .timeout(Duration::from_millis(10))
-                .open()
-            {
-                Ok(port) => port,
-                Err(error) => {
-                    error!(%error, "Unable to open serial port");
-
-                    // TODO: can cause super fast infinite loop when erroring out... Add a delay
-                    thread::sleep(Duration::from_secs(1));
-
-                    continue;
-                }
-            };
-
-            debug!("Connected to board");
-
-            sender
-                .send(SerialEvent::Connected)
-                .expect("unable to send SerialEvent");
-
-            port.write_data_terminal_ready(true).ok();
-
-            // TODO: better
-            match port.write_all(
-                postcard::to_allocvec_cobs(&PacketUp::Welcome)
-                    .expect("unable to serialize Welcome packet")
-                    .as_slice(),
-            ) {
-                Ok(()) => {}
-                Err(e) => match e.kind() {
-                    ErrorKind::TimedOut => {
-                        warn!("Serial port not connected");
-                        return;
-                    }
-                    _ => panic!("{}", e),
-                },
-            }
+impl Connection for SerialConnection {
+    fn peer(&self) -> String {
+        self.port.get_ref().name().unwrap_or_default()
+    }
 
-            let mut data_storage = Vec::with_capacity(phy::serial::BUFFER_SIZE);
-            let mut buffered_port = BufReader::with_capacity(9, port.as_mut());
-
-            loop {
-                let amount =
-                    match buffered_port.read_until(phy::serial::COBS_SENTINEL, &mut data_storage) {
-                        Ok(amount) => amount,
-                        Err(error) if error.kind() == ErrorKind::TimedOut => {
-                            /* Suppress time outs */
-                            continue;
-                        }
-                        Err(error) => {
-                            error!(%error, "Failed to communicate to serial port");
-                            break;
-                        }
-                    };
-
-                if amount > phy::serial::BUFFER_SIZE {
-                    trace!(
-                        "Received {} bytes more than expected over serial",
-                        amount - phy::serial::BUFFER_SIZE
-                    );
-                }
+    fn send(&mut self, packet: &PacketUp) -> io::Result<()> {
+        let frame = encode_frame(packet)?;
 
-                match postcard::from_bytes_cobs::<PacketDown>(&mut data_storage[..amount]) {
-                    Ok(packet) => match sender.send(SerialEvent::PacketReceived(packet)) {
-                        Ok(()) => {}
-                        Err(_) => {
-                            // End the thread if the channel has closed
-                            trace!("SerialEvent channel closed, shutting down thread");
-
-                            return;
-                        }
-                    },
-                    Err(error) => {
-                        error!(%error, "Failed to deserialize data");
-                        break;
-                    }
-                }
+        match self.port.get_mut().write_all(&frame) {
+            Err(error) if error.kind() == ErrorKind::TimedOut => {
+                warn!("Serial port not connected");
 
-                data_storage.clear();
+                Err(error)
             }
+            result => result,
+        }
+    }
 
-            // Set the DTR signal low if performing a graceful shutdown
-            port.write_data_terminal_ready(false).ok();
-
-            sender
-                .send(SerialEvent::Disconnected)
-                .expect("unable to send SerialEvent");
-
-            trace!("Closing serial connection");
+    fn receive(&mut self) -> Result<Option<PacketDown>, ReceiveError> {
+        read_frame(
+            &mut self.port,
+            &mut self.data_storage,
+            &mut self.bytes_received,
+        )
+    }
 
-            first_retry = true;
-        } else {
-            if first_retry {
-                warn!("No board connected");
-            }
-            first_retry = false;
-        }
+    fn bytes_received(&self) -> u64 {
+        self.bytes_received
+    }
 
-        thread::sleep(refresh_interval);
+    fn close(&mut self) {
+        // Set the DTR signal low if performing a graceful shutdown
+        self.port.get_mut().write_data_terminal_ready(false).ok();
     }
 }
 
+/// Find the vehicle's serial port, preferring the `SERIAL_PORT` environment
+/// variable over ports that enumerate as the board
 pub fn try_find_serial_port() -> Option<String> {
+    if let Some(port) = env::var("SERIAL_PORT")
+        .ok()
+        .filter(|port| Path::new(port).exists())
+    {
+        return Some(port);
+    }
+
     serialport::available_ports()
         .expect("unable to enumerate ports")
         .into_iter()
@@ -0,0 +1,97 @@
+use std::time::{Duration, Instant};
+
+use interlink::phy::frame::FrameError;
+
+/// How often statistics are reported to the application
+pub const REPORT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Silence longer than this between two packets is counted as a gap
+const GAP_THRESHOLD: Duration = Duration::from_secs(1);
+
+/// Health of the current link to the vehicle, as last reported by the transport
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LinkStats {
+    pub packets_per_second: f32,
+    pub bytes_per_second: f32,
+    /// Frames that passed their CRC but could not be decoded, or were not valid frames at all
+    pub decode_errors: u64,
+    /// Frames that were corrupted in transit
+    pub crc_failures: u64,
+    /// Periods where the vehicle went quiet
+    pub gaps: u64,
+    /// Percentage of recent frames that failed to decode
+    pub error_rate: u8,
+}
+
+/// Gathers [`LinkStats`] for a single connection
+#[derive(Debug)]
+pub struct StatsCollector {
+    stats: LinkStats,
+
+    /// Start of the current reporting interval
+    interval_start: Instant,
+    /// Packets received in the current interval
+    packets: u32,
+    /// Total bytes received as of the start of the current interval
+    bytes_at_interval_start: u64,
+    last_packet: Option<Instant>,
+}
+
+impl Default for StatsCollector {
+    fn default() -> Self {
+        Self {
+            stats: LinkStats::default(),
+            interval_start: Instant::now(),
+            packets: 0,
+            bytes_at_interval_start: 0,
+            last_packet: None,
+        }
+    }
+}
+
+impl StatsCollector {
+    pub fn packet_received(&mut self) {
+        let now = Instant::now();
+
+        if let Some(last_packet) = self.last_packet {
+            if now.duration_since(last_packet) > GAP_THRESHOLD {
+                self.stats.gaps += 1;
+            }
+        }
+
+        self.last_packet = Some(now);
+        self.packets += 1;
+    }
+
+    pub const fn frame_error(&mut self, error: &FrameError) {
+        match error {
+            FrameError::Checksum { .. } => self.stats.crc_failures += 1,
+            _ => self.stats.decode_errors += 1,
+        }
+    }
+
+    /// Finish the current interval if it is over, returning its statistics
+    ///
+    /// `bytes_received` is the total number of bytes received over the
+    /// connection and `error_rate` the percentage of recent frames that failed.
+    pub fn poll(&mut self, bytes_received: u64, error_rate: u8) -> Option<LinkStats> {
+        let elapsed = self.interval_start.elapsed();
+
+        if elapsed < REPORT_INTERVAL {
+            return None;
+        }
+
+        let seconds = elapsed.as_secs_f32();
+
+        self.stats.packets_per_second = self.packets as f32 / seconds;
+        self.stats.bytes_per_second =
+            bytes_received.saturating_sub(self.bytes_at_interval_start) as f32 / seconds;
+        self.stats.error_rate = error_rate;
+
+        self.interval_start = Instant::now();
+        self.packets = 0;
+        self.bytes_at_interval_start = bytes_received;
+
+        Some(self.stats)
+    }
+}
@@ -0,0 +1,85 @@
+use interlink::proto::PacketUp;
+use time::Duration;
+use tracing::{trace, warn};
+
+use crate::{
+    time_manager::{
+        clock_model::SyncSample,
+        unit::{LocalTime, VehicleTime},
+    },
+    util::env_or,
+};
+
+/// How many milliseconds to wait between time sync requests when `TIME_SYNC_INTERVAL_MS` is not set.
+const DEFAULT_INTERVAL_MS: u32 = 1000;
+
+/// Periodically asks the vehicle for its clock, working out from its answers
+/// how long the link takes and when the vehicle's clock read what
+#[derive(Debug)]
+pub struct TimeSync {
+    interval: Duration,
+    last_request: Option<LocalTime>,
+}
+
+impl TimeSync {
+    /// Create a synchronizer configured by the `TIME_SYNC_INTERVAL_MS` environment variable
+    pub fn from_env() -> Self {
+        Self {
+            interval: Duration::milliseconds(
+                env_or("TIME_SYNC_INTERVAL_MS", DEFAULT_INTERVAL_MS).into(),
+            ),
+            last_request: None,
+        }
+    }
+
+    /// Return a request to send if it has been long enough since the last one
+    pub fn poll(&mut self, now: LocalTime) -> Option<PacketUp> {
+        if let Some(last_request) = self.last_request {
+            if now.duration_since(&last_request) < self.interval {
+                return None;
+            }
+        }
+
+        self.last_request = Some(now);
+
+        Some(PacketUp::TimeSync {
+            station_time: now.unix_micros() as u64,
+        })
+    }
+
+    /// Send the next request as soon as a vehicle is connected again
+    pub const fn reset(&mut self) {
+        self.last_request = None;
+    }
+}
+
+/// Work out the four timestamps of an exchange, given the vehicle's answer
+/// and when it arrived
+///
+/// Answers that would have taken a negative time are discarded, as the
+/// station's clock must have been changed while they were in flight.
+pub fn exchange(
+    station_time: u64,
+    received: VehicleTime,
+    transmitted: VehicleTime,
+    arrived: LocalTime,
+) -> Option<SyncSample> {
+    let in_flight_micros = arrived.unix_micros() - station_time as i64;
+    let in_flight = Duration::microseconds(in_flight_micros);
+    let answering = transmitted.as_duration() - received.as_duration();
+    let round_trip = in_flight - answering;
+
+    if round_trip.is_negative() || answering.is_negative() {
+        warn!(%in_flight, %answering, "Discarding impossible time sync exchange");
+
+        return None;
+    }
+
+    trace!(%round_trip, "Time sync exchange");
+
+    Some(SyncSample {
+        vehicle: received.as_duration() + answering / 2,
+        station: arrived + Duration::microseconds(-in_flight_micros / 2),
+        round_trip,
+    })
+}
@@ -2,8 +2,15 @@ use iced::{tooltip::Position, Element, Row, Text, Tooltip};
 
 use crate::style::{self, colors::Color};
 
+pub mod command_controls;
+pub mod command_history;
 pub mod ground_station_status;
 pub mod instrument;
+pub mod mission_controls;
+pub mod power_alarm;
+pub mod protocol_banner;
+pub mod pyro_controls;
+pub mod replay_controls;
 pub mod telemetry_status;
 
 pub(self) fn mono_label_text_tooltip<'m, Message: 'm>(
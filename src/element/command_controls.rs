@@ -0,0 +1,201 @@
+use std::{
+    borrow::Cow,
+    fmt::{self, Display, Formatter},
+};
+
+use iced::{button, pick_list, Align, Button, Column, Element, PickList, Row, Text};
+use interlink::proto::{Command, Sensor};
+
+use crate::style;
+
+#[derive(Debug, Clone, Copy)]
+pub enum CommandMessage {
+    Arm,
+    Disarm,
+    Ping,
+    Reboot,
+    RequestIdentification,
+    SetTelemetryRate,
+    SelectSensor(Sensor),
+    SelectRate(TelemetryRate),
+}
+
+/// Buttons for commanding the connected vehicle
+#[derive(Debug)]
+pub struct CommandControls {
+    arm_button: button::State,
+    disarm_button: button::State,
+    ping_button: button::State,
+    reboot_button: button::State,
+    identify_button: button::State,
+    rate_button: button::State,
+    sensor_picker: pick_list::State<Sensor>,
+    rate_picker: pick_list::State<TelemetryRate>,
+
+    sensor: Sensor,
+    rate: TelemetryRate,
+    next_nonce: u32,
+}
+
+impl Default for CommandControls {
+    fn default() -> Self {
+        Self {
+            arm_button: button::State::default(),
+            disarm_button: button::State::default(),
+            ping_button: button::State::default(),
+            reboot_button: button::State::default(),
+            identify_button: button::State::default(),
+            rate_button: button::State::default(),
+            sensor_picker: pick_list::State::default(),
+            rate_picker: pick_list::State::default(),
+
+            sensor: Sensor::Accelerometer,
+            rate: TelemetryRate::Hz10,
+            next_nonce: 0,
+        }
+    }
+}
+
+impl CommandControls {
+    /// Handle a message, returning the command to send to the vehicle if any
+    pub const fn update(&mut self, message: CommandMessage) -> Option<Command> {
+        match message {
+            CommandMessage::Arm => Some(Command::Arm),
+            CommandMessage::Disarm => Some(Command::Disarm),
+            CommandMessage::Ping => {
+                let nonce = self.next_nonce;
+                self.next_nonce = self.next_nonce.wrapping_add(1);
+
+                Some(Command::Ping { nonce })
+            }
+            CommandMessage::Reboot => Some(Command::Reboot),
+            CommandMessage::RequestIdentification => Some(Command::RequestIdentification),
+            CommandMessage::SetTelemetryRate => Some(Command::SetTelemetryRate {
+                sensor: self.sensor,
+                rate: self.rate.hertz(),
+            }),
+            CommandMessage::SelectSensor(sensor) => {
+                self.sensor = sensor;
+
+                None
+            }
+            CommandMessage::SelectRate(rate) => {
+                self.rate = rate;
+
+                None
+            }
+        }
+    }
+
+    /// Buttons are only enabled while a vehicle is `connected`
+    pub fn view(&mut self, connected: bool) -> Element<'_, CommandMessage> {
+        let button = |state, label, message| {
+            let button = Button::new(state, Text::new(label)).style(style::ControlCluster);
+
+            if connected {
+                button.on_press(message)
+            } else {
+                button
+            }
+        };
+
+        Column::new()
+            .push(
+                Row::new()
+                    .push(button(&mut self.arm_button, "Arm", CommandMessage::Arm))
+                    .push(button(
+                        &mut self.disarm_button,
+                        "Disarm",
+                        CommandMessage::Disarm,
+                    ))
+                    .push(button(
+                        &mut self.reboot_button,
+                        "Reboot",
+                        CommandMessage::Reboot,
+                    ))
+                    .spacing(10),
+            )
+            .push(
+                Row::new()
+                    .push(button(&mut self.ping_button, "Ping", CommandMessage::Ping))
+                    .push(button(
+                        &mut self.identify_button,
+                        "Identify",
+                        CommandMessage::RequestIdentification,
+                    ))
+                    .spacing(10),
+            )
+            .push(
+                Row::new()
+                    .push(PickList::new(
+                        &mut self.sensor_picker,
+                        Cow::Borrowed(Sensor::ALL),
+                        Some(self.sensor),
+                        CommandMessage::SelectSensor,
+                    ))
+                    .push(PickList::new(
+                        &mut self.rate_picker,
+                        Cow::Borrowed(TelemetryRate::ALL),
+                        Some(self.rate),
+                        CommandMessage::SelectRate,
+                    ))
+                    .push(button(
+                        &mut self.rate_button,
+                        "Set Rate",
+                        CommandMessage::SetTelemetryRate,
+                    ))
+                    .spacing(10)
+                    .align_items(Align::Center),
+            )
+            .spacing(5)
+            .align_items(Align::Center)
+            .into()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TelemetryRate {
+    Off,
+    Hz1,
+    Hz2,
+    Hz5,
+    Hz10,
+    Hz20,
+    Hz50,
+    Hz100,
+}
+
+impl TelemetryRate {
+    pub const ALL: &'static [TelemetryRate] = &[
+        TelemetryRate::Off,
+        TelemetryRate::Hz1,
+        TelemetryRate::Hz2,
+        TelemetryRate::Hz5,
+        TelemetryRate::Hz10,
+        TelemetryRate::Hz20,
+        TelemetryRate::Hz50,
+        TelemetryRate::Hz100,
+    ];
+
+    pub const fn hertz(self) -> u16 {
+        match self {
+            TelemetryRate::Off => 0,
+            TelemetryRate::Hz1 => 1,
+            TelemetryRate::Hz2 => 2,
+            TelemetryRate::Hz5 => 5,
+            TelemetryRate::Hz10 => 10,
+            TelemetryRate::Hz20 => 20,
+            TelemetryRate::Hz50 => 50,
+            TelemetryRate::Hz100 => 100,
+        }
+    }
+}
+
+impl Display for TelemetryRate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            TelemetryRate::Off => write!(f, "Off"),
+            rate => write!(f, "{} Hz", rate.hertz()),
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use iced::{
+    tooltip::Position, Align, Column, Container, Element, HorizontalAlignment, Length, Row, Text,
+    Tooltip,
+};
+use interlink::proto::Command;
+
+use crate::{
+    comm::command::{is_repeatable, CommandStatus, CommandTracker, TrackedCommand},
+    style::{self, colors},
+};
+
+/// Number of commands shown in the history panel
+const VISIBLE_COMMANDS: usize = 10;
+
+pub fn command_history<'m, Message: 'm>(tracker: &CommandTracker) -> Element<'m, Message> {
+    let commands = tracker
+        .history()
+        .take(VISIBLE_COMMANDS)
+        .fold(Column::new().spacing(2), |column, tracked| {
+            column.push(tracked_command(tracked))
+        });
+
+    Container::new(
+        Column::new()
+            .push(
+                Text::new("Command History")
+                    .size(32)
+                    .horizontal_alignment(HorizontalAlignment::Center),
+            )
+            .push(commands)
+            .spacing(10)
+            .align_items(Align::Center)
+            .width(Length::Shrink)
+            .height(Length::Shrink),
+    )
+    .padding(10)
+    .style(style::ControlCluster)
+    .width(Length::Shrink)
+    .height(Length::Shrink)
+    .into()
+}
+
+fn tracked_command<'m, Message: 'm>(tracked: &TrackedCommand) -> Element<'m, Message> {
+    let (status, color, tooltip) = match &tracked.status {
+        CommandStatus::Pending if tracked.attempts > 1 => (
+            format!("Retry {}", tracked.attempts - 1),
+            colors::WARNING,
+            None,
+        ),
+        CommandStatus::Pending => (String::from("Pending"), colors::WARNING, None),
+        CommandStatus::Acknowledged { round_trip } => (
+            format!("ACK {} ms", round_trip.whole_milliseconds()),
+            colors::GOOD,
+            None,
+        ),
+        CommandStatus::Refused { reason, round_trip } => (
+            format!("NAK {} ms", round_trip.whole_milliseconds()),
+            colors::ERROR,
+            Some(reason.to_string()),
+        ),
+        CommandStatus::TimedOut if !is_repeatable(tracked.command) => (
+            String::from("Timed Out"),
+            colors::ERROR,
+            Some(String::from(
+                "Not resent as it may have been carried out, check the vehicle's status",
+            )),
+        ),
+        CommandStatus::TimedOut => (String::from("Timed Out"), colors::ERROR, None),
+        CommandStatus::Failed { reason } => {
+            (String::from("Failed"), colors::ERROR, Some(reason.clone()))
+        }
+    };
+
+    let status = Text::new(status)
+        .font(style::fonts::MONOSPACE)
+        .color(color)
+        .width(Length::Units(110));
+
+    let status: Element<'m, Message> = match tooltip {
+        Some(tooltip) => Tooltip::new(status, tooltip, Position::Top)
+            .style(style::Tooltip)
+            .into(),
+        None => status.into(),
+    };
+
+    Row::new()
+        .push(
+            Text::new(format!("#{:<5}", tracked.id))
+                .font(style::fonts::MONOSPACE)
+                .color(colors::SECONDARY_TEXT),
+        )
+        .push(
+            Text::new(describe(tracked.command))
+                .font(style::fonts::MONOSPACE)
+                .width(Length::Units(260)),
+        )
+        .push(status)
+        .spacing(10)
+        .into()
+}
+
+fn describe(command: Command) -> String {
+    match command {
+        Command::Arm => String::from("Arm"),
+        Command::Disarm => String::from("Disarm"),
+        Command::Ping { nonce } => format!("Ping {}", nonce),
+        Command::Reboot => String::from("Reboot"),
+        Command::RequestIdentification => String::from("Identify"),
+        Command::SetTelemetryRate { sensor, rate } => format!("{} {} Hz", sensor, rate),
+        Command::FirePyro { channel } => format!("Fire Pyro {}", u16::from(channel) + 1),
+    }
+}
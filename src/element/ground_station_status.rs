@@ -16,6 +16,7 @@ pub fn ground_station_status<'m, Message: 'm>(time_manager: &TimeManager) -> Ele
         .push(time_with_tooltip(time_manager, TimeBase::GroundControl))
         .push(time_with_tooltip(time_manager, TimeBase::VehicleTime))
         .push(time_with_tooltip(time_manager, TimeBase::Mission))
+        .push(clock_fit(time_manager))
         .push(Space::new(Length::Shrink, Length::Fill))
         .width(Length::Fill)
         .height(Length::Fill)
@@ -53,10 +54,44 @@ fn time_with_tooltip<'m, Message: 'm>(
         TimeBase::Mission => ("MIT", "Mission Time", Color::from_rgb(0x00, 0x00, 0xFF)),
     };
 
-    mono_label_text_tooltip(
-        label,
-        format_duration(time_manager.elapsed(time_base)),
-        tooltip,
-        Some(color),
+    let text = match time_base {
+        TimeBase::Mission => mission_time(time_manager),
+        _ => format_duration(time_manager.elapsed(time_base)),
+    };
+
+    mono_label_text_tooltip(label, text, tooltip, Some(color))
+}
+
+/// How well vehicle timestamps are being mapped onto station time
+fn clock_fit<'m, Message: 'm>(time_manager: &TimeManager) -> Element<'m, Message> {
+    match time_manager.clock_model().fit() {
+        Some(fit) => mono_label_text_tooltip(
+            "CLK",
+            format!("{:+.0} ppm ±{:.1} ms", fit.drift_ppm(), fit.jitter * 1000.0),
+            &format!(
+                "Vehicle clock drift and jitter, fitted to {} samples",
+                fit.samples
+            ),
+            None,
+        ),
+        None => mono_label_text_tooltip("CLK", "--", "Vehicle clock drift and jitter", None),
+    }
+}
+
+/// Mission time relative to liftoff, such as `T-00:00:10.0`
+fn mission_time(time_manager: &TimeManager) -> String {
+    let mission = time_manager.mission();
+
+    if !mission.is_started() {
+        return String::from("--:--:--.-");
+    }
+
+    let elapsed = time_manager.elapsed(TimeBase::Mission);
+
+    format!(
+        "T{}{}{}",
+        if elapsed.is_negative() { '-' } else { '+' },
+        format_duration(elapsed.abs()),
+        if mission.is_holding() { " HOLD" } else { "" }
     )
 }
@@ -1,11 +1,16 @@
-use iced::{button, Button, Container, Element, Length, Space};
+use iced::{button, Button, Element, Length};
 
 use crate::style;
 
 use self::data_view::{DataView, View};
 
+pub mod altimeter;
+pub mod attitude;
 pub mod data_view;
+pub mod position;
+pub mod power;
 pub mod reading;
+pub mod thermometer;
 pub mod time_series;
 pub mod vector;
 
@@ -14,18 +19,6 @@ pub enum InstrumentMessage {
     Selected(DataView),
 }
 
-pub struct PlaceholderInstrument {}
-
-impl PlaceholderInstrument {
-    pub fn view<'s>() -> Element<'s, InstrumentMessage> {
-        Container::new(Space::new(Length::Fill, Length::Fill))
-            .width(Length::Fill)
-            .height(Length::Fill)
-            .style(style::Instrument)
-            .into()
-    }
-}
-
 fn instrument_view<'s, V: View, E: Into<Element<'s, InstrumentMessage>> + 's>(
     content: E,
     button_state: &'s mut button::State,
@@ -0,0 +1,197 @@
+use iced::{
+    button, Column, Element, HorizontalAlignment, Length, Row, Space, Text, VerticalAlignment,
+};
+use interlink::proto::BarometerReading;
+
+use crate::{
+    style::{self, colors::Color},
+    time_manager::unit::VehicleTime,
+};
+
+use super::{
+    data_view::{Altitude, View},
+    instrument_view,
+    reading::Altitude as AltitudeReading,
+    InstrumentMessage,
+};
+
+/// Readings averaged into the pressure on the pad before any altitude is reported
+pub const REFERENCE_SAMPLES: u32 = 20;
+/// Time constant of the filter smoothing the vertical speed, in seconds
+const VERTICAL_SPEED_TIME_CONSTANT: f64 = 0.5;
+
+/// Height above the pad according to the barometer, with the highest point reached
+#[derive(Debug)]
+pub struct AltimeterInstrument {
+    /// Pressures read so far while capturing the reference, summed, and how many there were
+    reference_samples: (f64, u32),
+    /// Pressure on the pad in Pa, which altitudes are measured from
+    reference_pressure: Option<f64>,
+    /// Time and unfiltered altitude of the previous reading
+    previous: Option<(VehicleTime, f64)>,
+    altitude: Option<AltitudeReading>,
+    apogee: f64,
+
+    button_state: button::State,
+}
+
+impl AltimeterInstrument {
+    pub fn new() -> Self {
+        Self {
+            reference_samples: (0.0, 0),
+            reference_pressure: None,
+            previous: None,
+            altitude: None,
+            apogee: 0.0,
+
+            button_state: button::State::new(),
+        }
+    }
+
+    /// Convert a barometer reading to an altitude above the pad
+    ///
+    /// The first few readings are taken as the pressure on the pad, and give no altitude.
+    pub fn add_reading(
+        &mut self,
+        time: VehicleTime,
+        raw: BarometerReading,
+    ) -> Option<AltitudeReading> {
+        let pressure = raw.pressure as f64;
+
+        let reference_pressure = match self.reference_pressure {
+            Some(reference_pressure) => reference_pressure,
+            None => self.add_pad_reading(raw)?,
+        };
+
+        let altitude = pressure_altitude(pressure, reference_pressure);
+
+        let vertical_speed = match (self.previous, self.altitude) {
+            (Some((previous_time, previous_altitude)), Some(last)) => {
+                let dt = (time.as_duration() - previous_time.as_duration()).as_seconds_f64();
+
+                if dt > 0.0 {
+                    let speed = (altitude - previous_altitude) / dt;
+                    let smoothing = 1.0 - (-dt / VERTICAL_SPEED_TIME_CONSTANT).exp();
+
+                    last.vertical_speed + (speed - last.vertical_speed) * smoothing
+                } else {
+                    last.vertical_speed
+                }
+            }
+            _ => 0.0,
+        };
+
+        let reading = AltitudeReading {
+            altitude,
+            vertical_speed,
+            temperature: raw.temperature as f64 / 100.0,
+        };
+
+        self.previous = Some((time, altitude));
+        self.altitude = Some(reading);
+        self.apogee = self.apogee.max(altitude);
+
+        Some(reading)
+    }
+
+    /// Take a reading into the pressure on the pad, returning that pressure
+    /// once enough readings have been taken
+    ///
+    /// Readings are ignored once the pressure on the pad is known.
+    pub fn add_pad_reading(&mut self, raw: BarometerReading) -> Option<f64> {
+        if self.reference_pressure.is_none() {
+            let (sum, count) = &mut self.reference_samples;
+            *sum += raw.pressure as f64;
+            *count += 1;
+
+            if *count >= REFERENCE_SAMPLES {
+                self.reference_pressure = Some(*sum / *count as f64);
+            }
+        }
+
+        self.reference_pressure
+    }
+
+    /// Capture the pressure on the pad again and forget the apogee, as the
+    /// vehicle may have been moved or another flight may be about to start
+    pub const fn reset(&mut self) {
+        self.reference_samples = (0.0, 0);
+        self.reference_pressure = None;
+        self.previous = None;
+        self.altitude = None;
+        self.apogee = 0.0;
+    }
+
+    pub fn view(&mut self, big: bool) -> Element<InstrumentMessage> {
+        let (headline_size, text_size) = if big { (96, 32) } else { (40, 16) };
+
+        let line = |text: String, color: Color| {
+            Text::new(text)
+                .font(style::fonts::MONOSPACE)
+                .size(text_size)
+                .color(color)
+                .vertical_alignment(VerticalAlignment::Center)
+        };
+
+        let column = Column::new()
+            .push(
+                Text::new(Altitude::TITLE)
+                    .size(text_size + 4)
+                    .horizontal_alignment(HorizontalAlignment::Left),
+            )
+            .spacing(2)
+            .padding(5)
+            .width(Length::Fill)
+            .height(Length::Fill);
+
+        let column = match (self.reference_pressure, self.altitude) {
+            (Some(reference_pressure), Some(altitude)) => column
+                .push(
+                    Text::new(format!("{:.1} m", altitude.altitude))
+                        .font(style::fonts::MONOSPACE)
+                        .size(headline_size)
+                        .color(style::colors::ACCENT),
+                )
+                .push(line(
+                    format!("Apogee: {:.1} m", self.apogee),
+                    style::colors::TEXT,
+                ))
+                .push(line(
+                    format!("V/S: {:+.1} m/s", altitude.vertical_speed),
+                    style::colors::TEXT,
+                ))
+                .push(Space::with_height(Length::Fill))
+                .push(
+                    Row::new()
+                        .push(line(
+                            format!("Pad: {:.0} Pa", reference_pressure),
+                            style::colors::SECONDARY_TEXT,
+                        ))
+                        .push(line(
+                            format!("{:.1} °C", altitude.temperature),
+                            style::colors::SECONDARY_TEXT,
+                        ))
+                        .spacing(20),
+                ),
+            _ if self.reference_samples.1 > 0 => column.push(line(
+                format!(
+                    "Capturing pad pressure ({}/{})",
+                    self.reference_samples.1, REFERENCE_SAMPLES
+                ),
+                style::colors::WARNING,
+            )),
+            _ => column.push(line(
+                String::from("No barometer data"),
+                style::colors::SECONDARY_TEXT,
+            )),
+        };
+
+        instrument_view::<Altitude, _>(column, &mut self.button_state)
+    }
+}
+
+/// Height in meters above where the pressure is `reference_pressure`, in the
+/// international standard atmosphere
+fn pressure_altitude(pressure: f64, reference_pressure: f64) -> f64 {
+    44_330.77 * (1.0 - (pressure / reference_pressure).powf(0.190_263))
+}
@@ -0,0 +1,379 @@
+use std::f32::consts::{FRAC_PI_2, PI};
+
+use iced::{
+    button,
+    canvas::{self, path::Arc, Cursor, Frame, Geometry, LineCap, Path, Program, Stroke},
+    Canvas, Column, Element, HorizontalAlignment, Length, Point, Rectangle, Size, Text, Vector,
+    VerticalAlignment,
+};
+
+use crate::{attitude::quaternion::Quaternion, style};
+
+use super::{
+    data_view::{Attitude, View},
+    instrument_view,
+    reading::Attitude as AttitudeReading,
+    InstrumentMessage,
+};
+
+/// Pitch in degrees from the horizon to the edge of the indicator
+const PITCH_RANGE: f32 = 45.0;
+/// Headings in degrees either side of the current one shown on the tape
+const HEADING_RANGE: f32 = 45.0;
+/// Height of the heading tape in pixels
+const TAPE_HEIGHT: f32 = 30.0;
+/// Bank angles in degrees marked on the roll arc
+const ROLL_MARKS: [f32; 11] = [
+    -60.0, -45.0, -30.0, -20.0, -10.0, 0.0, 10.0, 20.0, 30.0, 45.0, 60.0,
+];
+
+/// Artificial horizon showing the vehicle's estimated orientation
+#[derive(Debug)]
+pub struct AttitudeInstrument {
+    reading: Option<AttitudeReading>,
+
+    button_state: button::State,
+}
+
+impl AttitudeInstrument {
+    pub fn new() -> Self {
+        Self {
+            reading: None,
+
+            button_state: button::State::new(),
+        }
+    }
+
+    pub fn set_reading(&mut self, raw: Quaternion) {
+        self.reading.replace(Attitude::ingest_reading(raw));
+    }
+
+    pub fn view(&mut self, big: bool) -> Element<InstrumentMessage> {
+        let text_size = if big { 24 } else { 16 };
+
+        let summary = match self.reading {
+            Some(reading) => format!(
+                "R {:+06.1}°  P {:+05.1}°  HDG {:03.0}°",
+                reading.roll, reading.pitch, reading.heading
+            ),
+            None => String::from("No attitude estimate"),
+        };
+
+        instrument_view::<Attitude, _>(
+            Column::new()
+                .push(
+                    Text::new(Attitude::TITLE)
+                        .size(text_size + 4)
+                        .horizontal_alignment(HorizontalAlignment::Left),
+                )
+                .push(
+                    Canvas::new(AttitudeIndicatorView {
+                        reading: self.reading,
+                    })
+                    .width(Length::Fill)
+                    .height(Length::Fill),
+                )
+                .push(
+                    Text::new(summary)
+                        .font(style::fonts::MONOSPACE)
+                        .size(text_size)
+                        .color(style::colors::SECONDARY_TEXT)
+                        .vertical_alignment(VerticalAlignment::Center),
+                )
+                .spacing(2)
+                .padding(5)
+                .width(Length::Fill)
+                .height(Length::Fill),
+            &mut self.button_state,
+        )
+    }
+}
+
+/// Pitch ladder and roll arc inside a circle, under a heading tape
+#[derive(Debug)]
+pub struct AttitudeIndicatorView {
+    reading: Option<AttitudeReading>,
+}
+
+impl Program<InstrumentMessage> for AttitudeIndicatorView {
+    fn draw(&self, bounds: Rectangle, _cursor: Cursor) -> Vec<Geometry> {
+        let margin = 10.0;
+
+        let mut frame = Frame::new(bounds.size());
+
+        let radius = (frame.width() / 2.0).min((frame.height() - TAPE_HEIGHT) / 2.0) - margin;
+        if radius <= 0.0 {
+            return vec![frame.into_geometry()];
+        }
+
+        let center = Point::new(frame.width() / 2.0, TAPE_HEIGHT + margin + radius);
+
+        let (roll, pitch, heading) = match self.reading {
+            Some(reading) => (
+                reading.roll as f32,
+                reading.pitch as f32,
+                reading.heading as f32,
+            ),
+            None => {
+                frame.stroke(
+                    &Path::circle(center, radius),
+                    Stroke {
+                        color: style::colors::GRID_LINES.into(),
+                        width: 1.0,
+                        ..Default::default()
+                    },
+                );
+
+                return vec![frame.into_geometry()];
+            }
+        };
+
+        let pixels_per_degree = radius / PITCH_RANGE;
+
+        // The horizon and everything fixed to it rolls the opposite way to the vehicle
+        frame.with_save(|frame| {
+            frame.translate(Vector::new(center.x, center.y));
+            frame.rotate(-roll.to_radians());
+
+            draw_horizon(frame, radius, pitch * pixels_per_degree);
+            draw_pitch_ladder(frame, radius, pitch, pixels_per_degree);
+
+            // Pointer to the top of the sky, read against the fixed roll arc
+            frame.fill(
+                &Path::new(|path| {
+                    path.move_to(Point::new(0.0, -radius + 2.0));
+                    path.line_to(Point::new(-6.0, -radius + 12.0));
+                    path.line_to(Point::new(6.0, -radius + 12.0));
+                    path.close();
+                }),
+                iced::Color::from(style::colors::TEXT),
+            );
+        });
+
+        draw_roll_arc(&mut frame, center, radius);
+        draw_vehicle_symbol(&mut frame, center, radius);
+        draw_heading_tape(&mut frame, heading);
+
+        vec![frame.into_geometry()]
+    }
+}
+
+/// Fill the circle with sky, and the part of it below the horizon with ground,
+/// `offset` pixels below the center
+fn draw_horizon(frame: &mut Frame, radius: f32, offset: f32) {
+    let origin = Point::ORIGIN;
+
+    frame.fill(
+        &Path::circle(origin, radius),
+        iced::Color::from(style::colors::SKY),
+    );
+
+    if offset <= -radius {
+        frame.fill(
+            &Path::circle(origin, radius),
+            iced::Color::from(style::colors::GROUND),
+        );
+    } else if offset < radius {
+        let half_width = (radius * radius - offset * offset).sqrt();
+        let start_angle = offset.atan2(half_width);
+
+        frame.fill(
+            &Path::new(|path| {
+                path.move_to(Point::new(half_width, offset));
+                path.arc(Arc {
+                    center: origin,
+                    radius,
+                    start_angle,
+                    end_angle: PI - start_angle,
+                });
+                path.close();
+            }),
+            iced::Color::from(style::colors::GROUND),
+        );
+
+        frame.stroke(
+            &Path::line(
+                Point::new(-half_width, offset),
+                Point::new(half_width, offset),
+            ),
+            Stroke {
+                color: style::colors::TEXT.into(),
+                width: 2.0,
+                ..Default::default()
+            },
+        );
+    }
+}
+
+/// Rungs every 10° of pitch, within the part of the circle clear of the roll arc
+fn draw_pitch_ladder(frame: &mut Frame, radius: f32, pitch: f32, pixels_per_degree: f32) {
+    let visible = radius * 0.75;
+
+    for rung in (-9..=9).map(|rung| rung as f32 * 10.0) {
+        if rung == 0.0 {
+            continue;
+        }
+
+        let y = (pitch - rung) * pixels_per_degree;
+        if y.abs() > visible {
+            continue;
+        }
+
+        let half_width = if rung as i32 % 20 == 0 {
+            radius * 0.3
+        } else {
+            radius * 0.15
+        };
+
+        frame.stroke(
+            &Path::line(Point::new(-half_width, y), Point::new(half_width, y)),
+            Stroke {
+                color: style::colors::TEXT.into(),
+                width: 1.0,
+                ..Default::default()
+            },
+        );
+
+        frame.fill_text(canvas::Text {
+            content: format!("{:.0}", rung),
+            position: Point::new(half_width + 4.0, y),
+            color: style::colors::TEXT.into(),
+            size: 12.0,
+            vertical_alignment: VerticalAlignment::Center,
+            ..Default::default()
+        });
+    }
+}
+
+/// Fixed scale of bank angles around the top of the circle
+fn draw_roll_arc(frame: &mut Frame, center: Point, radius: f32) {
+    let stroke = Stroke {
+        color: style::colors::TEXT.into(),
+        width: 1.5,
+        ..Default::default()
+    };
+
+    frame.stroke(
+        &Path::new(|path| {
+            path.arc(Arc {
+                center,
+                radius,
+                start_angle: -FRAC_PI_2 - 60f32.to_radians(),
+                end_angle: -FRAC_PI_2 + 60f32.to_radians(),
+            })
+        }),
+        stroke,
+    );
+
+    for mark in ROLL_MARKS {
+        let angle = -FRAC_PI_2 + mark.to_radians();
+        let length = if mark as i32 % 30 == 0 { 12.0 } else { 6.0 };
+
+        frame.stroke(
+            &Path::line(
+                Point::new(
+                    center.x + radius * angle.cos(),
+                    center.y + radius * angle.sin(),
+                ),
+                Point::new(
+                    center.x + (radius + length) * angle.cos(),
+                    center.y + (radius + length) * angle.sin(),
+                ),
+            ),
+            stroke,
+        );
+    }
+}
+
+/// Wings and a dot fixed to the center, for the horizon to be read against
+fn draw_vehicle_symbol(frame: &mut Frame, center: Point, radius: f32) {
+    let wing = radius * 0.35;
+
+    frame.stroke(
+        &Path::new(|path| {
+            path.move_to(Point::new(center.x - wing, center.y));
+            path.line_to(Point::new(center.x - wing / 3.0, center.y));
+            path.line_to(Point::new(center.x - wing / 6.0, center.y + wing / 6.0));
+            path.move_to(Point::new(center.x + wing, center.y));
+            path.line_to(Point::new(center.x + wing / 3.0, center.y));
+            path.line_to(Point::new(center.x + wing / 6.0, center.y + wing / 6.0));
+        }),
+        Stroke {
+            color: style::colors::ACCENT.into(),
+            width: 3.0,
+            line_cap: LineCap::Round,
+            ..Default::default()
+        },
+    );
+
+    frame.fill(
+        &Path::circle(center, 3.0),
+        iced::Color::from(style::colors::ACCENT),
+    );
+}
+
+/// Strip along the top showing the headings either side of the current one
+fn draw_heading_tape(frame: &mut Frame, heading: f32) {
+    let width = frame.width();
+    let pixels_per_degree = width / 2.0 / HEADING_RANGE;
+
+    frame.fill_rectangle(
+        Point::ORIGIN,
+        Size::new(width, TAPE_HEIGHT),
+        iced::Color::from(style::colors::BACKGROUND),
+    );
+
+    let stroke = Stroke {
+        color: style::colors::TEXT.into(),
+        width: 1.0,
+        ..Default::default()
+    };
+
+    let first = ((heading - HEADING_RANGE) / 5.0).ceil() as i32 * 5;
+    let last = ((heading + HEADING_RANGE) / 5.0).floor() as i32 * 5;
+
+    for mark in (first..=last).step_by(5) {
+        let x = width / 2.0 + (mark as f32 - heading) * pixels_per_degree;
+        let mark = mark.rem_euclid(360);
+
+        let length = if mark % 10 == 0 { 8.0 } else { 4.0 };
+
+        frame.stroke(
+            &Path::line(
+                Point::new(x, TAPE_HEIGHT - length),
+                Point::new(x, TAPE_HEIGHT),
+            ),
+            stroke,
+        );
+
+        if mark % 30 == 0 {
+            let label = match mark {
+                0 => String::from("N"),
+                90 => String::from("E"),
+                180 => String::from("S"),
+                270 => String::from("W"),
+                _ => format!("{:03}", mark),
+            };
+
+            frame.fill_text(canvas::Text {
+                content: label,
+                position: Point::new(x, 2.0),
+                color: style::colors::TEXT.into(),
+                size: 14.0,
+                horizontal_alignment: HorizontalAlignment::Center,
+                ..Default::default()
+            });
+        }
+    }
+
+    // Index at the current heading
+    frame.fill(
+        &Path::new(|path| {
+            path.move_to(Point::new(width / 2.0, TAPE_HEIGHT));
+            path.line_to(Point::new(width / 2.0 - 5.0, TAPE_HEIGHT + 7.0));
+            path.line_to(Point::new(width / 2.0 + 5.0, TAPE_HEIGHT + 7.0));
+            path.close();
+        }),
+        iced::Color::from(style::colors::ACCENT),
+    );
+}
@@ -1,8 +1,12 @@
 use std::fmt::Debug;
 
-use interlink::proto::Vector3;
+use interlink::proto::{GnssFix, PowerReading, Vector3, MAX_CELLS};
 
-use super::reading::Reading;
+use crate::attitude::quaternion::Quaternion;
+
+use super::reading::{
+    Altitude as AltitudeReading, Attitude as AttitudeReading, Fix, Power as PowerState, Reading,
+};
 
 pub trait View: 'static + Debug {
     type Reading: Reading;
@@ -20,6 +24,10 @@ pub enum DataView {
     Accelerometer,
     Magnetometer,
     Temperature,
+    Position,
+    Altitude,
+    Attitude,
+    Power,
 }
 
 impl DataView {
@@ -83,3 +91,98 @@ impl View for Temperature {
         raw as f64
     }
 }
+
+#[derive(Debug, Clone, Copy)]
+pub struct Position;
+
+impl View for Position {
+    type Reading = Fix;
+    type Raw = GnssFix;
+
+    const DATA_VIEW: DataView = DataView::Position;
+
+    const TITLE: &'static str = "Position";
+
+    fn ingest_reading(raw: Self::Raw) -> Self::Reading {
+        Fix {
+            fix: raw.fix,
+            satellites: raw.satellites,
+            latitude: raw.latitude as f64 / 1e7,
+            longitude: raw.longitude as f64 / 1e7,
+            altitude: raw.altitude as f64 / 1000.0,
+            hdop: raw.hdop as f64 / 100.0,
+            ground_speed: raw.ground_speed as f64 / 1000.0,
+            course: raw.course as f64 / 100.0,
+        }
+    }
+}
+
+/// Altitude above the pad, as worked out by the
+/// [`AltimeterInstrument`](super::altimeter::AltimeterInstrument) from the
+/// barometer and the pressure on the pad
+#[derive(Debug, Clone, Copy)]
+pub struct Altitude;
+
+impl View for Altitude {
+    type Reading = AltitudeReading;
+    type Raw = AltitudeReading;
+
+    const DATA_VIEW: DataView = DataView::Altitude;
+
+    const TITLE: &'static str = "Altitude";
+
+    fn ingest_reading(raw: Self::Raw) -> Self::Reading {
+        raw
+    }
+}
+
+/// Orientation of the vehicle, as estimated from all of its motion sensors
+#[derive(Debug, Clone, Copy)]
+pub struct Attitude;
+
+impl View for Attitude {
+    type Reading = AttitudeReading;
+    type Raw = Quaternion;
+
+    const DATA_VIEW: DataView = DataView::Attitude;
+
+    const TITLE: &'static str = "Attitude";
+
+    fn ingest_reading(raw: Self::Raw) -> Self::Reading {
+        let angles = raw.euler_angles();
+
+        AttitudeReading {
+            roll: angles.roll.to_degrees(),
+            pitch: angles.pitch.to_degrees(),
+            // Yaw turns towards the west, while headings turn towards the east
+            heading: (-angles.yaw.to_degrees()).rem_euclid(360.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Power;
+
+impl View for Power {
+    type Reading = PowerState;
+    type Raw = PowerReading;
+
+    const DATA_VIEW: DataView = DataView::Power;
+
+    const TITLE: &'static str = "Power";
+
+    fn ingest_reading(raw: Self::Raw) -> Self::Reading {
+        let mut cells = [0.0; MAX_CELLS];
+        for (cell, raw) in cells.iter_mut().zip(&raw.cells) {
+            *cell = *raw as f64 / 1000.0;
+        }
+
+        PowerState {
+            bus_voltage: raw.bus_voltage as f64 / 1000.0,
+            current: raw.current as f64 / 1000.0,
+            cells,
+            cell_count: raw.cells.len(),
+            state_of_charge: raw.state_of_charge,
+        }
+    }
+}
@@ -0,0 +1,264 @@
+use std::collections::VecDeque;
+
+use iced::{
+    button,
+    canvas::{self, Cursor, Frame, Geometry, Path, Program, Stroke},
+    Canvas, Column, Element, HorizontalAlignment, Length, Point, Rectangle, Row, Text,
+    VerticalAlignment,
+};
+use interlink::proto::{FixType, GnssFix};
+
+use crate::style::{self, colors::Color};
+
+use super::{
+    data_view::{Position, View},
+    instrument_view,
+    reading::Fix,
+    InstrumentMessage,
+};
+
+/// Mean radius of the earth in meters
+const EARTH_RADIUS: f64 = 6_371_000.0;
+/// Number of positions kept in the ground track
+const TRACK_LENGTH: usize = 4096;
+/// Smallest distance from the launch point the ground track is scaled to show, in meters
+const MIN_TRACK_EXTENT: f32 = 50.0;
+
+/// Current GNSS fix alongside the ground track since launch
+#[derive(Debug)]
+pub struct PositionInstrument {
+    fix: Option<Fix>,
+    /// Latitude and longitude of the first fix, which the track is relative to
+    launch_point: Option<(f64, f64)>,
+    /// Meters east and north of the launch point, oldest first
+    track: VecDeque<(f32, f32)>,
+
+    button_state: button::State,
+}
+
+impl PositionInstrument {
+    pub fn new() -> Self {
+        Self {
+            fix: None,
+            launch_point: None,
+            track: VecDeque::with_capacity(TRACK_LENGTH),
+
+            button_state: button::State::new(),
+        }
+    }
+
+    pub fn add_reading(&mut self, raw: GnssFix) {
+        let fix = Position::ingest_reading(raw);
+        self.fix.replace(fix);
+
+        if fix.fix == FixType::NoFix {
+            return;
+        }
+
+        let (launch_latitude, launch_longitude) = *self
+            .launch_point
+            .get_or_insert((fix.latitude, fix.longitude));
+
+        // Flights are short enough for an equirectangular projection
+        let north = (fix.latitude - launch_latitude).to_radians() * EARTH_RADIUS;
+        let east = (fix.longitude - launch_longitude).to_radians()
+            * EARTH_RADIUS
+            * launch_latitude.to_radians().cos();
+
+        if self.track.len() == TRACK_LENGTH {
+            self.track.pop_front();
+        }
+
+        self.track.push_back((east as f32, north as f32));
+    }
+
+    pub fn view(&mut self, big: bool) -> Element<InstrumentMessage> {
+        let text_size = if big { 24 } else { 16 };
+
+        instrument_view::<Position, _>(
+            Row::new()
+                .push(
+                    readout(self.fix, self.track.back().copied(), text_size)
+                        .width(Length::Shrink)
+                        .height(Length::Fill),
+                )
+                .push(
+                    Canvas::new(GroundTrackView { track: &self.track })
+                        .width(Length::Fill)
+                        .height(Length::Fill),
+                )
+                .spacing(10)
+                .padding(5)
+                .width(Length::Fill)
+                .height(Length::Fill),
+            &mut self.button_state,
+        )
+    }
+}
+
+fn readout<'e>(
+    fix: Option<Fix>,
+    position: Option<(f32, f32)>,
+    text_size: u16,
+) -> Column<'e, InstrumentMessage> {
+    let line = |text: String, color: Color| {
+        Text::new(text)
+            .font(style::fonts::MONOSPACE)
+            .size(text_size)
+            .color(color)
+            .vertical_alignment(VerticalAlignment::Center)
+    };
+
+    let column = Column::new()
+        .push(
+            Text::new(Position::TITLE)
+                .size(text_size + 4)
+                .horizontal_alignment(HorizontalAlignment::Left),
+        )
+        .spacing(2);
+
+    let fix = match fix {
+        Some(fix) => fix,
+        None => {
+            return column.push(line(
+                String::from("No GNSS data"),
+                style::colors::SECONDARY_TEXT,
+            ))
+        }
+    };
+
+    let fix_color = match fix.fix {
+        FixType::Fix3D => style::colors::GOOD,
+        FixType::Fix2D => style::colors::WARNING,
+        FixType::NoFix => style::colors::ERROR,
+    };
+
+    let column = column.push(line(
+        format!("Fix: {} ({} sats)", fix.fix, fix.satellites),
+        fix_color,
+    ));
+
+    if fix.fix == FixType::NoFix {
+        return column;
+    }
+
+    let range = position.map_or(0.0, |(east, north)| east.hypot(north));
+
+    column
+        .push(line(format!("HDOP: {:.2}", fix.hdop), style::colors::TEXT))
+        .push(line(
+            format!(
+                "{:.7}° {}",
+                fix.latitude.abs(),
+                if fix.latitude >= 0.0 { 'N' } else { 'S' }
+            ),
+            style::colors::TEXT,
+        ))
+        .push(line(
+            format!(
+                "{:.7}° {}",
+                fix.longitude.abs(),
+                if fix.longitude >= 0.0 { 'E' } else { 'W' }
+            ),
+            style::colors::TEXT,
+        ))
+        .push(line(
+            if fix.fix == FixType::Fix3D {
+                format!("Alt: {:.1} m", fix.altitude)
+            } else {
+                String::from("Alt: ---")
+            },
+            style::colors::TEXT,
+        ))
+        .push(line(
+            format!("GS: {:.1} m/s {:03.0}°", fix.ground_speed, fix.course),
+            style::colors::TEXT,
+        ))
+        .push(line(
+            format!("Range: {:.0} m", range),
+            style::colors::ACCENT,
+        ))
+}
+
+/// Path of the vehicle over the ground, with north up and the launch point in the center
+#[derive(Debug)]
+pub struct GroundTrackView<'i> {
+    track: &'i VecDeque<(f32, f32)>,
+}
+
+impl<'i> Program<InstrumentMessage> for GroundTrackView<'i> {
+    fn draw(&self, bounds: Rectangle, _cursor: Cursor) -> Vec<Geometry> {
+        let margin = 10.0;
+
+        let mut frame = Frame::new(bounds.size());
+        let center = frame.center();
+        let radius = frame.width().min(frame.height()) / 2.0 - margin;
+
+        let extent = self
+            .track
+            .iter()
+            .map(|&(east, north)| east.abs().max(north.abs()))
+            .fold(MIN_TRACK_EXTENT, f32::max);
+        let scale = radius / extent;
+
+        let to_point = |&(east, north): &(f32, f32)| {
+            Point::new(center.x + east * scale, center.y - north * scale)
+        };
+
+        // Cross hairs on the launch point
+        frame.stroke(
+            &Path::new(|path| {
+                path.move_to(Point::new(center.x - radius, center.y));
+                path.line_to(Point::new(center.x + radius, center.y));
+                path.move_to(Point::new(center.x, center.y - radius));
+                path.line_to(Point::new(center.x, center.y + radius));
+            }),
+            Stroke {
+                color: style::colors::GRID_LINES.into(),
+                width: 1.0,
+                ..Default::default()
+            },
+        );
+
+        frame.fill_text(canvas::Text {
+            content: String::from("N"),
+            position: Point::new(center.x + 4.0, center.y - radius),
+            color: style::colors::SECONDARY_TEXT.into(),
+            ..Default::default()
+        });
+
+        frame.fill_text(canvas::Text {
+            content: format!("{:.0} m", extent),
+            position: Point::new(center.x + radius, center.y + 4.0),
+            color: style::colors::SECONDARY_TEXT.into(),
+            horizontal_alignment: HorizontalAlignment::Right,
+            ..Default::default()
+        });
+
+        if let Some(first) = self.track.front() {
+            frame.stroke(
+                &Path::new(|path| {
+                    path.move_to(to_point(first));
+
+                    for position in self.track.iter().skip(1) {
+                        path.line_to(to_point(position));
+                    }
+                }),
+                Stroke {
+                    color: style::colors::ACCENT.into(),
+                    width: 1.5,
+                    ..Default::default()
+                },
+            );
+        }
+
+        if let Some(last) = self.track.back() {
+            frame.fill(
+                &Path::circle(to_point(last), 4.0),
+                iced::Color::from(style::colors::TEXT),
+            );
+        }
+
+        vec![frame.into_geometry()]
+    }
+}
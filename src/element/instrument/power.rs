@@ -0,0 +1,334 @@
+use std::{
+    f32::consts::PI,
+    fmt::{self, Display, Formatter},
+};
+
+use iced::{
+    button,
+    canvas::{self, path::Arc, Cursor, Frame, Geometry, LineCap, Path, Program, Stroke},
+    Canvas, Column, Element, HorizontalAlignment, Length, Point, Rectangle, Row, Text,
+    VerticalAlignment,
+};
+use interlink::proto::PowerReading;
+use tracing::{error, info, warn};
+
+use crate::{
+    style::{self, colors::Color},
+    util::env_or,
+};
+
+use super::{
+    data_view::{Power, View},
+    instrument_view,
+    reading::Power as PowerState,
+    InstrumentMessage,
+};
+
+/// Bus voltage in volts below which a warning is raised when `BUS_VOLTAGE_WARNING` is not set
+const DEFAULT_BUS_WARNING: f64 = 7.0;
+/// Bus voltage in volts below which the alarm is critical when `BUS_VOLTAGE_CRITICAL` is not set
+const DEFAULT_BUS_CRITICAL: f64 = 6.6;
+/// Cell voltage in volts below which a warning is raised when `CELL_VOLTAGE_WARNING` is not set
+const DEFAULT_CELL_WARNING: f64 = 3.5;
+/// Cell voltage in volts below which the alarm is critical when `CELL_VOLTAGE_CRITICAL` is not set
+const DEFAULT_CELL_CRITICAL: f64 = 3.3;
+/// Fraction a voltage must recover above its threshold before the alarm clears,
+/// so noise does not toggle it
+const HYSTERESIS: f64 = 0.02;
+
+/// How serious a low voltage is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AlarmLevel {
+    Warning,
+    Critical,
+}
+
+impl AlarmLevel {
+    pub const fn color(self) -> Color {
+        match self {
+            AlarmLevel::Warning => style::colors::WARNING,
+            AlarmLevel::Critical => style::colors::ERROR,
+        }
+    }
+}
+
+impl Display for AlarmLevel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            AlarmLevel::Warning => write!(f, "Low voltage"),
+            AlarmLevel::Critical => write!(f, "Critical voltage"),
+        }
+    }
+}
+
+/// Voltages below which an alarm is raised
+#[derive(Debug, Clone, Copy)]
+struct Thresholds {
+    warning: f64,
+    critical: f64,
+}
+
+impl Thresholds {
+    fn level(self, voltage: f64, previous: Option<AlarmLevel>) -> Option<AlarmLevel> {
+        let margin = |level| {
+            if previous >= Some(level) {
+                1.0 + HYSTERESIS
+            } else {
+                1.0
+            }
+        };
+
+        if voltage < self.critical * margin(AlarmLevel::Critical) {
+            Some(AlarmLevel::Critical)
+        } else if voltage < self.warning * margin(AlarmLevel::Warning) {
+            Some(AlarmLevel::Warning)
+        } else {
+            None
+        }
+    }
+}
+
+/// A low voltage alarm and the reading that raised it
+#[derive(Debug, Clone, Copy)]
+pub struct LowVoltage {
+    pub level: AlarmLevel,
+    pub reading: PowerState,
+}
+
+/// Latest state of the battery, with a gauge colored by how close it is to the alarm thresholds
+#[derive(Debug)]
+pub struct PowerInstrument {
+    reading: Option<PowerState>,
+    alarm: Option<AlarmLevel>,
+
+    bus: Thresholds,
+    cell: Thresholds,
+
+    button_state: button::State,
+}
+
+impl PowerInstrument {
+    pub fn from_env() -> Self {
+        Self {
+            reading: None,
+            alarm: None,
+
+            bus: Thresholds {
+                warning: env_or("BUS_VOLTAGE_WARNING", DEFAULT_BUS_WARNING),
+                critical: env_or("BUS_VOLTAGE_CRITICAL", DEFAULT_BUS_CRITICAL),
+            },
+            cell: Thresholds {
+                warning: env_or("CELL_VOLTAGE_WARNING", DEFAULT_CELL_WARNING),
+                critical: env_or("CELL_VOLTAGE_CRITICAL", DEFAULT_CELL_CRITICAL),
+            },
+
+            button_state: button::State::new(),
+        }
+    }
+
+    pub fn add_reading(&mut self, raw: PowerReading) {
+        let reading = Power::ingest_reading(raw);
+        self.reading.replace(reading);
+
+        let bus_level = self.bus.level(reading.bus_voltage, self.alarm);
+        let cell_level = reading
+            .lowest_cell()
+            .and_then(|cell| self.cell.level(cell, self.alarm));
+        let alarm = bus_level.max(cell_level);
+
+        if alarm != self.alarm {
+            match alarm {
+                Some(AlarmLevel::Critical) => error!(
+                    bus_voltage = reading.bus_voltage,
+                    lowest_cell = ?reading.lowest_cell(),
+                    "Vehicle battery voltage critical"
+                ),
+                Some(AlarmLevel::Warning) => warn!(
+                    bus_voltage = reading.bus_voltage,
+                    lowest_cell = ?reading.lowest_cell(),
+                    "Vehicle battery voltage low"
+                ),
+                None => info!(
+                    bus_voltage = reading.bus_voltage,
+                    "Vehicle battery voltage recovered"
+                ),
+            }
+        }
+
+        self.alarm = alarm;
+    }
+
+    /// The alarm raised by the latest reading, if any
+    pub fn alarm(&self) -> Option<LowVoltage> {
+        Some(LowVoltage {
+            level: self.alarm?,
+            reading: self.reading?,
+        })
+    }
+
+    pub fn view(&mut self, big: bool) -> Element<InstrumentMessage> {
+        let text_size = if big { 24 } else { 16 };
+
+        let line = |text: String, color: Color| {
+            Text::new(text)
+                .font(style::fonts::MONOSPACE)
+                .size(text_size)
+                .color(color)
+                .vertical_alignment(VerticalAlignment::Center)
+        };
+
+        let mut readout = Column::new()
+            .push(
+                Text::new(Power::TITLE)
+                    .size(text_size + 4)
+                    .horizontal_alignment(HorizontalAlignment::Left),
+            )
+            .spacing(2)
+            .width(Length::Shrink)
+            .height(Length::Fill);
+
+        match self.reading {
+            Some(reading) => {
+                readout = readout
+                    .push(line(
+                        format!("Bus: {:.2} V", reading.bus_voltage),
+                        self.bus_color(reading.bus_voltage),
+                    ))
+                    .push(line(
+                        format!("Load: {:.2} A", reading.current),
+                        style::colors::TEXT,
+                    ));
+
+                for (index, &cell) in reading.cells().iter().enumerate() {
+                    readout = readout.push(line(
+                        format!("Cell {}: {:.3} V", index + 1, cell),
+                        self.cell_color(cell),
+                    ));
+                }
+            }
+            None => {
+                readout = readout.push(line(
+                    String::from("No power data"),
+                    style::colors::SECONDARY_TEXT,
+                ))
+            }
+        }
+
+        let color = match self.alarm {
+            Some(level) => level.color(),
+            None => style::colors::GOOD,
+        };
+
+        instrument_view::<Power, _>(
+            Row::new()
+                .push(readout)
+                .push(
+                    Canvas::new(PowerGaugeView {
+                        reading: self.reading,
+                        color,
+                    })
+                    .width(Length::Fill)
+                    .height(Length::Fill),
+                )
+                .spacing(10)
+                .padding(5)
+                .width(Length::Fill)
+                .height(Length::Fill),
+            &mut self.button_state,
+        )
+    }
+
+    fn bus_color(&self, voltage: f64) -> Color {
+        match self.bus.level(voltage, None) {
+            Some(level) => level.color(),
+            None => style::colors::TEXT,
+        }
+    }
+
+    fn cell_color(&self, voltage: f64) -> Color {
+        match self.cell.level(voltage, None) {
+            Some(level) => level.color(),
+            None => style::colors::TEXT,
+        }
+    }
+}
+
+/// Arc filled to the battery's state of charge, around its bus voltage
+#[derive(Debug)]
+pub struct PowerGaugeView {
+    reading: Option<PowerState>,
+    color: Color,
+}
+
+impl Program<InstrumentMessage> for PowerGaugeView {
+    fn draw(&self, bounds: Rectangle, _cursor: Cursor) -> Vec<Geometry> {
+        let margin = 10.0;
+        let width = 10.0;
+
+        let mut frame = Frame::new(bounds.size());
+        let center = frame.center();
+        let radius = frame.width().min(frame.height()) / 2.0 - margin;
+
+        // The gauge sweeps clockwise through the top, leaving a gap at the bottom
+        let start_angle = PI * 0.75;
+        let sweep = PI * 1.5;
+
+        let arc = |fraction: f32| {
+            Path::new(|path| {
+                path.arc(Arc {
+                    center,
+                    radius,
+                    start_angle,
+                    end_angle: start_angle + sweep * fraction,
+                })
+            })
+        };
+
+        frame.stroke(
+            &arc(1.0),
+            Stroke {
+                color: style::colors::GRID_LINES.into(),
+                width,
+                line_cap: LineCap::Round,
+                ..Default::default()
+            },
+        );
+
+        let reading = match self.reading {
+            Some(reading) => reading,
+            None => return vec![frame.into_geometry()],
+        };
+
+        frame.stroke(
+            &arc(f32::from(reading.state_of_charge.min(100)) / 100.0),
+            Stroke {
+                color: self.color.into(),
+                width,
+                line_cap: LineCap::Round,
+                ..Default::default()
+            },
+        );
+
+        frame.fill_text(canvas::Text {
+            content: format!("{:.2} V", reading.bus_voltage),
+            position: center,
+            color: self.color.into(),
+            size: (radius / 2.5).max(12.0),
+            horizontal_alignment: HorizontalAlignment::Center,
+            vertical_alignment: VerticalAlignment::Center,
+            ..Default::default()
+        });
+
+        frame.fill_text(canvas::Text {
+            content: format!("{}%", reading.state_of_charge),
+            position: Point::new(center.x, center.y + radius * 0.6),
+            color: style::colors::SECONDARY_TEXT.into(),
+            size: (radius / 5.0).max(12.0),
+            horizontal_alignment: HorizontalAlignment::Center,
+            vertical_alignment: VerticalAlignment::Center,
+            ..Default::default()
+        });
+
+        vec![frame.into_geometry()]
+    }
+}
@@ -1,6 +1,6 @@
 use std::{fmt::Debug, iter, ops::Range};
 
-use interlink::proto::Vector3;
+use interlink::proto::{FixType, Vector3, MAX_CELLS};
 use plotters::style::{ShapeStyle, BLUE, GREEN, RED};
 
 pub type ReadingValuesIter<S> =
@@ -116,3 +116,236 @@ impl Reading for f64 {
         .into()
     }
 }
+
+/// A GNSS fix in SI units
+#[derive(Debug, Clone, Copy)]
+pub struct Fix {
+    pub fix: FixType,
+    pub satellites: u8,
+    /// Degrees north
+    pub latitude: f64,
+    /// Degrees east
+    pub longitude: f64,
+    /// Meters above mean sea level
+    pub altitude: f64,
+    pub hdop: f64,
+    /// Meters per second
+    pub ground_speed: f64,
+    /// Degrees clockwise from north
+    pub course: f64,
+}
+
+impl Reading for Fix {
+    const VALUES: usize = 2;
+
+    /// Altitude is NaN, and left off the chart, unless the fix is 3D
+    fn value(&self, index: usize) -> f64 {
+        match index {
+            0 if self.fix == FixType::Fix3D => self.altitude,
+            0 => f64::NAN,
+            1 => self.ground_speed,
+            _ => panic!(
+                "attempted to access value out of bounds: {} > {}",
+                index,
+                Self::VALUES - 1
+            ),
+        }
+    }
+
+    fn label(index: usize) -> &'static str {
+        match index {
+            0 => "Altitude (m)",
+            1 => "Ground Speed (m/s)",
+            _ => panic!(
+                "attempted to access label out of bounds: {} > {}",
+                index,
+                Self::VALUES - 1
+            ),
+        }
+    }
+
+    fn style(index: usize) -> ShapeStyle {
+        match index {
+            0 => RED,
+            1 => GREEN,
+            _ => panic!(
+                "attempted to access style out of bounds: {} > {}",
+                index,
+                Self::VALUES - 1
+            ),
+        }
+        .into()
+    }
+}
+
+/// Height above the launch pad derived from the barometer
+#[derive(Debug, Clone, Copy)]
+pub struct Altitude {
+    /// Meters above the pad
+    pub altitude: f64,
+    /// Meters per second, positive upwards
+    pub vertical_speed: f64,
+    /// Degrees celsius
+    pub temperature: f64,
+}
+
+impl Reading for Altitude {
+    const VALUES: usize = 2;
+
+    fn value(&self, index: usize) -> f64 {
+        match index {
+            0 => self.altitude,
+            1 => self.vertical_speed,
+            _ => panic!(
+                "attempted to access value out of bounds: {} > {}",
+                index,
+                Self::VALUES - 1
+            ),
+        }
+    }
+
+    fn label(index: usize) -> &'static str {
+        match index {
+            0 => "Altitude AGL (m)",
+            1 => "Vertical Speed (m/s)",
+            _ => panic!(
+                "attempted to access label out of bounds: {} > {}",
+                index,
+                Self::VALUES - 1
+            ),
+        }
+    }
+
+    fn style(index: usize) -> ShapeStyle {
+        match index {
+            0 => RED,
+            1 => GREEN,
+            _ => panic!(
+                "attempted to access style out of bounds: {} > {}",
+                index,
+                Self::VALUES - 1
+            ),
+        }
+        .into()
+    }
+}
+
+/// Orientation of the vehicle, where standing upright on the pad is level
+#[derive(Debug, Clone, Copy)]
+pub struct Attitude {
+    /// Degrees, positive when banked right
+    pub roll: f64,
+    /// Degrees, positive when tilted nose up
+    pub pitch: f64,
+    /// Degrees clockwise from north
+    pub heading: f64,
+}
+
+impl Reading for Attitude {
+    const VALUES: usize = 3;
+
+    fn value(&self, index: usize) -> f64 {
+        match index {
+            0 => self.roll,
+            1 => self.pitch,
+            2 => self.heading,
+            _ => panic!(
+                "attempted to access value out of bounds: {} > {}",
+                index,
+                Self::VALUES - 1
+            ),
+        }
+    }
+
+    fn label(index: usize) -> &'static str {
+        match index {
+            0 => "Roll (°)",
+            1 => "Pitch (°)",
+            2 => "Heading (°)",
+            _ => panic!(
+                "attempted to access label out of bounds: {} > {}",
+                index,
+                Self::VALUES - 1
+            ),
+        }
+    }
+
+    fn style(index: usize) -> ShapeStyle {
+        match index {
+            0 => RED,
+            1 => GREEN,
+            2 => BLUE,
+            _ => panic!(
+                "attempted to access style out of bounds: {} > {}",
+                index,
+                Self::VALUES - 1
+            ),
+        }
+        .into()
+    }
+}
+
+/// State of the vehicle's battery in SI units
+#[derive(Debug, Clone, Copy)]
+pub struct Power {
+    /// Volts
+    pub bus_voltage: f64,
+    /// Amps drawn from the battery
+    pub current: f64,
+    /// Volts, of which the first `cell_count` are monitored
+    pub cells: [f64; MAX_CELLS],
+    pub cell_count: usize,
+    /// Percent
+    pub state_of_charge: u8,
+}
+
+impl Power {
+    pub fn cells(&self) -> &[f64] {
+        &self.cells[..self.cell_count]
+    }
+
+    pub fn lowest_cell(&self) -> Option<f64> {
+        self.cells().iter().copied().reduce(f64::min)
+    }
+}
+
+impl Reading for Power {
+    const VALUES: usize = 2;
+
+    fn value(&self, index: usize) -> f64 {
+        match index {
+            0 => self.bus_voltage,
+            1 => self.current,
+            _ => panic!(
+                "attempted to access value out of bounds: {} > {}",
+                index,
+                Self::VALUES - 1
+            ),
+        }
+    }
+
+    fn label(index: usize) -> &'static str {
+        match index {
+            0 => "Bus Voltage (V)",
+            1 => "Current (A)",
+            _ => panic!(
+                "attempted to access label out of bounds: {} > {}",
+                index,
+                Self::VALUES - 1
+            ),
+        }
+    }
+
+    fn style(index: usize) -> ShapeStyle {
+        match index {
+            0 => RED,
+            1 => GREEN,
+            _ => panic!(
+                "attempted to access style out of bounds: {} > {}",
+                index,
+                Self::VALUES - 1
+            ),
+        }
+        .into()
+    }
+}
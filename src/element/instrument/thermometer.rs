@@ -0,0 +1,207 @@
+use iced::{
+    button, Column, Element, HorizontalAlignment, Length, Row, Space, Text, VerticalAlignment,
+};
+use tracing::{info, warn};
+
+use crate::{
+    style::{self, colors::Color},
+    util::env_or,
+};
+
+use super::{
+    data_view::{Temperature, View},
+    instrument_view,
+    time_series::TimeSeriesInstrument,
+    InstrumentMessage,
+};
+
+/// Temperature in °C below which a warning is raised when `TEMPERATURE_WARNING_LOW` is not set
+const DEFAULT_WARNING_LOW: f64 = -10.0;
+/// Temperature in °C above which a warning is raised when `TEMPERATURE_WARNING_HIGH` is not set
+const DEFAULT_WARNING_HIGH: f64 = 60.0;
+
+/// Summary of the temperatures within the time series' window
+#[derive(Debug, Clone, Copy)]
+struct Statistics {
+    min: f64,
+    max: f64,
+    mean: f64,
+    /// Change in °C per second from a least squares fit, if the readings span any time
+    rate: Option<f64>,
+}
+
+/// Latest temperature with its spread and trend over the readings kept by
+/// the temperature time series
+#[derive(Debug)]
+pub struct ThermometerInstrument {
+    warning_low: f64,
+    warning_high: f64,
+    /// The latest reading is outside the warning thresholds
+    warning: bool,
+
+    button_state: button::State,
+}
+
+impl ThermometerInstrument {
+    pub fn from_env() -> Self {
+        Self {
+            warning_low: env_or("TEMPERATURE_WARNING_LOW", DEFAULT_WARNING_LOW),
+            warning_high: env_or("TEMPERATURE_WARNING_HIGH", DEFAULT_WARNING_HIGH),
+            warning: false,
+
+            button_state: button::State::new(),
+        }
+    }
+
+    /// Raise or clear the warning for a new reading
+    pub fn add_reading(&mut self, raw: f32) {
+        let temperature = Temperature::ingest_reading(raw);
+
+        let warning = !self.within_thresholds(temperature);
+
+        if warning != self.warning {
+            if warning {
+                warn!(
+                    temperature,
+                    low = self.warning_low,
+                    high = self.warning_high,
+                    "Vehicle temperature outside warning thresholds"
+                );
+            } else {
+                info!(temperature, "Vehicle temperature back within thresholds");
+            }
+        }
+
+        self.warning = warning;
+    }
+
+    pub fn view(
+        &mut self,
+        series: &TimeSeriesInstrument<Temperature>,
+        big: bool,
+    ) -> Element<InstrumentMessage> {
+        let (headline_size, text_size) = if big { (96, 32) } else { (40, 16) };
+
+        let line = |text: String, color: Color| {
+            Text::new(text)
+                .font(style::fonts::MONOSPACE)
+                .size(text_size)
+                .color(color)
+                .vertical_alignment(VerticalAlignment::Center)
+        };
+
+        let column = Column::new()
+            .push(
+                Text::new(Temperature::TITLE)
+                    .size(text_size + 4)
+                    .horizontal_alignment(HorizontalAlignment::Left),
+            )
+            .spacing(2)
+            .padding(5)
+            .width(Length::Fill)
+            .height(Length::Fill);
+
+        let column = match (series.readings().last(), statistics(series)) {
+            (Some((_, temperature)), Some(statistics)) => column
+                .push(
+                    Text::new(format!("{:.1} °C", temperature))
+                        .font(style::fonts::MONOSPACE)
+                        .size(headline_size)
+                        .color(if self.warning {
+                            style::colors::WARNING
+                        } else {
+                            style::colors::ACCENT
+                        }),
+                )
+                .push(
+                    Row::new()
+                        .push(line(
+                            format!("Min: {:.1} °C", statistics.min),
+                            self.color(statistics.min),
+                        ))
+                        .push(line(
+                            format!("Max: {:.1} °C", statistics.max),
+                            self.color(statistics.max),
+                        ))
+                        .spacing(20),
+                )
+                .push(line(
+                    format!("Mean: {:.1} °C", statistics.mean),
+                    self.color(statistics.mean),
+                ))
+                .push(line(
+                    match statistics.rate {
+                        Some(rate) => format!("Rate: {:+.2} °C/min", rate * 60.0),
+                        None => String::from("Rate: --"),
+                    },
+                    style::colors::TEXT,
+                ))
+                .push(Space::with_height(Length::Fill))
+                .push(line(
+                    format!(
+                        "Over {:.0} s, warn outside {:.1} to {:.1} °C",
+                        series.width(),
+                        self.warning_low,
+                        self.warning_high
+                    ),
+                    style::colors::SECONDARY_TEXT,
+                )),
+            _ => column.push(line(
+                String::from("No temperature data"),
+                style::colors::SECONDARY_TEXT,
+            )),
+        };
+
+        instrument_view::<Temperature, _>(column, &mut self.button_state)
+    }
+
+    fn within_thresholds(&self, temperature: f64) -> bool {
+        (self.warning_low..=self.warning_high).contains(&temperature)
+    }
+
+    fn color(&self, temperature: f64) -> Color {
+        if self.within_thresholds(temperature) {
+            style::colors::TEXT
+        } else {
+            style::colors::WARNING
+        }
+    }
+}
+
+/// Statistics of the readings in the time series, if it has any
+fn statistics(series: &TimeSeriesInstrument<Temperature>) -> Option<Statistics> {
+    let (first_time, _) = series.readings().next()?;
+    let count = series.readings().count() as f64;
+
+    // Seconds since the first reading, so the fit is not thrown off by large times
+    let points = || {
+        series.readings().map(move |(time, temperature)| {
+            (
+                (time.as_duration() - first_time.as_duration()).as_seconds_f64(),
+                temperature,
+            )
+        })
+    };
+
+    let (min, max, sum_t, sum_y) = points().fold(
+        (f64::INFINITY, f64::NEG_INFINITY, 0.0, 0.0),
+        |(min, max, sum_t, sum_y), (t, y)| (min.min(y), max.max(y), sum_t + t, sum_y + y),
+    );
+
+    let mean_t = sum_t / count;
+    let mean = sum_y / count;
+
+    let (covariance, variance) = points().fold((0.0, 0.0), |(covariance, variance), (t, y)| {
+        (
+            covariance + (t - mean_t) * (y - mean),
+            variance + (t - mean_t) * (t - mean_t),
+        )
+    });
+
+    Some(Statistics {
+        min,
+        max,
+        mean,
+        rate: (variance > 0.0).then(|| covariance / variance),
+    })
+}
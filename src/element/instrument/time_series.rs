@@ -1,6 +1,7 @@
 use std::{collections::VecDeque, fmt::Debug, ops::Range};
 
 use iced::{button, Element};
+use interlink::proto::FlightPhase;
 use plotters::prelude::*;
 use plotters_iced::{Chart, ChartBuilder, ChartWidget, DrawingBackend};
 
@@ -11,10 +12,26 @@ use crate::{
 
 use super::{data_view::View, instrument_view, reading::Reading, InstrumentMessage};
 
+/// How many times longer than the interval before it the interval to a
+/// reading must be for it to follow lost readings of its own
+const GAP_TOLERANCE: f64 = 1.5;
+
+#[derive(Debug, Clone, Copy)]
+struct Sample<R> {
+    time: VehicleTime,
+    reading: R,
+    /// Readings were lost between the previous sample and this one
+    after_gap: bool,
+}
+
 #[derive(Debug)]
 pub struct TimeSeriesInstrument<V: View> {
-    readings: VecDeque<(VehicleTime, V::Reading)>,
+    readings: VecDeque<Sample<V::Reading>>,
     width: f64,
+    /// Packets have been lost since the last reading, though maybe not this series'
+    gap_pending: bool,
+    /// When the vehicle entered each phase of flight, oldest first
+    phases: VecDeque<(VehicleTime, FlightPhase)>,
 
     button_state: button::State,
 }
@@ -24,6 +41,8 @@ impl<V: View> TimeSeriesInstrument<V> {
         Self {
             readings: VecDeque::new(),
             width,
+            gap_pending: false,
+            phases: VecDeque::new(),
 
             button_state: button::State::new(),
         }
@@ -40,6 +59,7 @@ impl<V: View> TimeSeriesInstrument<V> {
                 time_manager,
                 time_base,
                 readings: &self.readings,
+                phases: &self.phases,
                 width: self.width,
                 big,
             }),
@@ -53,25 +73,91 @@ impl<V: View> TimeSeriesInstrument<V> {
         // but this is not really a good one
         let reading = V::ingest_reading(raw);
 
+        // The lost packets may have carried other sensors' readings, which only
+        // shows in how long it has been since this series' last reading
+        let after_gap = self.gap_pending && self.is_late(vehicle_time);
+
         self.readings.retain({
             let max_duration = vehicle_time.as_duration();
             let width = self.width;
 
-            move |&(time, _)| {
-                let difference = (max_duration - time.as_duration()).as_seconds_f64();
+            move |sample| {
+                let difference = (max_duration - sample.time.as_duration()).as_seconds_f64();
                 difference > 0.0 && difference < width
             }
         });
 
+        // Phases only scroll out of the window, as rewinding a replay starts the
+        // instruments over. Keep the phase the oldest reading was taken in, even
+        // if it started earlier
+        while let Some(&(next_start, _)) = self.phases.get(1) {
+            let age = (vehicle_time.as_duration() - next_start.as_duration()).as_seconds_f64();
+
+            if age < self.width {
+                break;
+            }
+
+            self.phases.pop_front();
+        }
+
         // dbg!(self.readings.len()); TODO:
 
-        self.readings.push_back((vehicle_time, reading));
+        self.readings.push_back(Sample {
+            time: vehicle_time,
+            reading,
+            after_gap,
+        });
+        self.gap_pending = false;
+    }
+
+    /// Whether `time` follows the last reading by noticeably longer than the
+    /// interval between the two readings before it
+    fn is_late(&self, time: VehicleTime) -> bool {
+        let mut recent = self
+            .readings
+            .iter()
+            .rev()
+            .map(|sample| sample.time.as_duration());
+
+        match (recent.next(), recent.next()) {
+            (Some(last), Some(before)) => {
+                let interval = (time.as_duration() - last).as_seconds_f64();
+                let expected = (last - before).as_seconds_f64();
+
+                interval > expected * GAP_TOLERANCE
+            }
+            _ => false,
+        }
+    }
+
+    /// Readings within the window, oldest first
+    pub fn readings(&self) -> impl Iterator<Item = (VehicleTime, V::Reading)> + '_ {
+        self.readings
+            .iter()
+            .map(|sample| (sample.time, sample.reading))
+    }
+
+    /// Seconds of readings kept
+    pub const fn width(&self) -> f64 {
+        self.width
+    }
+
+    /// Break the line before the next reading if packets of this series were
+    /// among those lost
+    pub const fn mark_gap(&mut self) {
+        self.gap_pending = true;
+    }
+
+    /// Shade the chart from `time` onwards in the color of the new flight phase
+    pub fn mark_phase(&mut self, time: VehicleTime, phase: FlightPhase) {
+        self.phases.push_back((time, phase));
     }
 }
 
 #[derive(Debug)]
 pub struct TimeSeriesInstrumentView<'i, V: View> {
-    readings: &'i VecDeque<(VehicleTime, V::Reading)>,
+    readings: &'i VecDeque<Sample<V::Reading>>,
+    phases: &'i VecDeque<(VehicleTime, FlightPhase)>,
     width: f64,
 
     big: bool,
@@ -86,8 +172,14 @@ impl<'i, V: View> TimeSeriesInstrumentView<'i, V> {
 
         let current_seconds = current_time.as_seconds_f64();
 
-        let x_min = (current_seconds - self.width).max(0.0);
-        let x_max = current_seconds.max(self.width);
+        let (x_min, x_max) = match self.time_base {
+            // Mission time runs on through zero from the countdown, so the window just follows it
+            TimeBase::Mission => (current_seconds - self.width, current_seconds),
+            _ => (
+                (current_seconds - self.width).max(0.0),
+                current_seconds.max(self.width),
+            ),
+        };
 
         x_min..x_max
     }
@@ -96,7 +188,7 @@ impl<'i, V: View> TimeSeriesInstrumentView<'i, V> {
         let (min, max) = self
             .readings
             .iter()
-            .map(|(_time, value)| *value)
+            .map(|sample| sample.reading)
             .flat_map(|datum| datum.values())
             .fold((f64::NAN, f64::NAN), |(pre_min, pre_max), value| {
                 (value.min(pre_min), value.max(pre_max))
@@ -126,9 +218,57 @@ impl<'i, V: View> Chart<InstrumentMessage> for TimeSeriesInstrumentView<'i, V> {
             .x_label_area_size(25)
             .y_label_area_size(40)
             // Finally attach a coordinate on the drawing area and make a chart context
-            .build_cartesian_2d(x_range.clone(), y_range)
+            .build_cartesian_2d(x_range.clone(), y_range.clone())
             .expect("failed to build time series chart");
 
+        // Shade the background behind each phase of flight, under the mesh and readings
+        let bands = self
+            .phases
+            .iter()
+            .enumerate()
+            .filter_map(|(index, &(start, phase))| {
+                let start = self
+                    .time_manager
+                    .rebase_vehicle_time(start, self.time_base)
+                    .as_seconds_f64();
+                let end = match self.phases.get(index + 1) {
+                    Some(&(end, _)) => self
+                        .time_manager
+                        .rebase_vehicle_time(end, self.time_base)
+                        .as_seconds_f64(),
+                    None => x_range.end,
+                };
+
+                if end <= x_range.start || start >= x_range.end {
+                    return None;
+                }
+
+                Some((start.max(x_range.start), end.min(x_range.end), phase))
+            })
+            .collect::<Vec<_>>();
+
+        chart
+            .draw_series(bands.iter().map(|&(start, end, phase)| {
+                Rectangle::new(
+                    [(start, y_range.start), (end, y_range.end)],
+                    style::colors::flight_phase(phase).mix(0.15).filled(),
+                )
+            }))
+            .expect("failed to draw flight phases");
+
+        if self.big {
+            chart
+                .draw_series(bands.iter().map(|&(start, _, phase)| {
+                    Text::new(
+                        phase.to_string(),
+                        (start, y_range.end),
+                        FontDesc::new(FontFamily::SansSerif, 12.0, FontStyle::Normal)
+                            .color(&style::colors::flight_phase(phase)),
+                    )
+                }))
+                .expect("failed to draw flight phase labels");
+        }
+
         let axis_label_style = FontDesc::new(FontFamily::SansSerif, 12.0, FontStyle::Normal)
             .color(&style::colors::TEXT);
 
@@ -136,7 +276,7 @@ impl<'i, V: View> Chart<InstrumentMessage> for TimeSeriesInstrumentView<'i, V> {
         chart
             .configure_mesh()
             .axis_style(ShapeStyle::from(&style::colors::AXIS.mix(0.45)).stroke_width(1))
-            .bold_line_style(&style::colors::GRID_LINES)
+            .bold_line_style(style::colors::GRID_LINES)
             // Disable minor grid lines
             .light_line_style(&plotters::style::TRANSPARENT)
             .label_style(
@@ -159,26 +299,47 @@ impl<'i, V: View> Chart<InstrumentMessage> for TimeSeriesInstrumentView<'i, V> {
         // TODO: make sure this is tracking correctly cause uh oh
         // TODO: Separate when zoom in?
         for i in 0..V::Reading::VALUES {
-            let series = self.readings.iter().filter_map(|&(vehicle_time, datum)| {
+            // Draw each run of readings between gaps separately, so no line is drawn across a gap
+            let mut segments = vec![Vec::new()];
+
+            for sample in self.readings {
                 let time = self
                     .time_manager
-                    .rebase_vehicle_time(vehicle_time, self.time_base)
+                    .rebase_vehicle_time(sample.time, self.time_base)
                     .as_seconds_f64();
 
                 if time < x_range.start {
-                    None
-                } else {
-                    Some((time, datum.value(i)))
+                    continue;
                 }
-            });
 
-            chart
-                .draw_series(LineSeries::new(series, V::Reading::style(i)))
-                .expect("failed to draw time series")
-                .label(V::Reading::label(i))
-                .legend(move |(x, y)| {
-                    PathElement::new([(x, y), (x + 20, y)], V::Reading::style(i))
-                });
+                let value = sample.reading.value(i);
+
+                // Values that are not known break the line just like a gap
+                if sample.after_gap || value.is_nan() {
+                    segments.push(Vec::new());
+                }
+
+                if value.is_nan() {
+                    continue;
+                }
+
+                if let Some(segment) = segments.last_mut() {
+                    segment.push((time, value));
+                }
+            }
+
+            for (index, segment) in segments.into_iter().enumerate() {
+                let series = chart
+                    .draw_series(LineSeries::new(segment, V::Reading::style(i)))
+                    .expect("failed to draw time series");
+
+                // Only one legend entry per value
+                if index == 0 {
+                    series.label(V::Reading::label(i)).legend(move |(x, y)| {
+                        PathElement::new([(x, y), (x + 20, y)], V::Reading::style(i))
+                    });
+                }
+            }
         }
 
         if self.big {
@@ -68,15 +68,15 @@ where
             Row::new()
                 .push(self.heading(
                     |reading| (reading.x(), reading.y()),
-                    format!("Yaw{}", if big { " (atan y/x)" } else { "" }),
+                    format!("X-Y{}", if big { " (atan y/x)" } else { "" }),
                 ))
                 .push(self.heading(
                     |reading| (reading.y(), reading.z()),
-                    format!("Pitch{}", if big { " (atan z/y)" } else { "" }),
+                    format!("Y-Z{}", if big { " (atan z/y)" } else { "" }),
                 ))
                 .push(self.heading(
                     |reading| (reading.x(), reading.z()),
-                    format!("Roll{}", if big { " (atan z/x)" } else { "" }),
+                    format!("X-Z{}", if big { " (atan z/x)" } else { "" }),
                 ))
                 .width(Length::Fill)
                 .height(Length::Fill),
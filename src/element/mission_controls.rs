@@ -0,0 +1,198 @@
+use std::{
+    borrow::Cow,
+    fmt::{self, Display, Formatter},
+};
+
+use iced::{button, pick_list, Align, Button, Column, Element, PickList, Row, Text};
+use time::Duration;
+use tracing::{info, warn};
+
+use crate::{
+    style,
+    time_manager::{base::TimeBase, format_duration, TimeManager},
+};
+
+use super::mono_label_text;
+
+#[derive(Debug, Clone, Copy)]
+pub enum MissionMessage {
+    Start,
+    Hold,
+    Resume,
+    Reset,
+    SelectCountdown(Countdown),
+}
+
+/// Buttons for starting, holding and resetting mission time
+#[derive(Debug)]
+pub struct MissionControls {
+    start_button: button::State,
+    hold_button: button::State,
+    reset_button: button::State,
+    countdown_picker: pick_list::State<Countdown>,
+
+    countdown: Countdown,
+}
+
+impl Default for MissionControls {
+    fn default() -> Self {
+        Self {
+            start_button: button::State::default(),
+            hold_button: button::State::default(),
+            reset_button: button::State::default(),
+            countdown_picker: pick_list::State::default(),
+
+            countdown: Countdown::Seconds10,
+        }
+    }
+}
+
+impl MissionControls {
+    pub fn update(&mut self, message: MissionMessage, time: &mut TimeManager) {
+        match message {
+            MissionMessage::Start => {
+                info!(countdown = %self.countdown, "Mission clock started");
+
+                time.start_mission(self.countdown.duration());
+            }
+            MissionMessage::Hold => {
+                if !time.mission().can_hold(time.now()) {
+                    warn!("Mission clock can only be held during the countdown");
+
+                    return;
+                }
+
+                time.hold_mission();
+
+                info!(
+                    mission_time = %format_duration(time.elapsed(TimeBase::Mission)),
+                    "Mission clock held"
+                );
+            }
+            MissionMessage::Resume => {
+                time.resume_mission();
+
+                info!(
+                    mission_time = %format_duration(time.elapsed(TimeBase::Mission)),
+                    "Mission clock resumed"
+                );
+            }
+            MissionMessage::Reset => {
+                info!("Mission clock reset");
+
+                time.reset_mission();
+            }
+            MissionMessage::SelectCountdown(countdown) => self.countdown = countdown,
+        }
+    }
+
+    pub fn view(&mut self, time: &TimeManager) -> Element<'_, MissionMessage> {
+        let mission = time.mission();
+
+        let (state, color) = if !mission.is_started() {
+            ("Not started", style::colors::SECONDARY_TEXT)
+        } else if mission.is_holding() {
+            ("Holding", style::colors::WARNING)
+        } else if time.elapsed(TimeBase::Mission).is_negative() {
+            ("Counting down", style::colors::ACCENT)
+        } else {
+            ("Running", style::colors::GOOD)
+        };
+
+        // The clock must be reset before it can be started again, so a stray
+        // press cannot lose the mission time
+        let start_button =
+            Button::new(&mut self.start_button, Text::new("Start")).style(style::ControlCluster);
+        let start_button = if mission.is_started() {
+            start_button
+        } else {
+            start_button.on_press(MissionMessage::Start)
+        };
+
+        let (hold_label, hold_message) = if mission.is_holding() {
+            ("Resume", MissionMessage::Resume)
+        } else {
+            ("Hold", MissionMessage::Hold)
+        };
+        let hold_button =
+            Button::new(&mut self.hold_button, Text::new(hold_label)).style(style::ControlCluster);
+        let hold_button = if mission.is_holding() || mission.can_hold(time.now()) {
+            hold_button.on_press(hold_message)
+        } else {
+            hold_button
+        };
+
+        let reset_button =
+            Button::new(&mut self.reset_button, Text::new("Reset")).style(style::ControlCluster);
+        let reset_button = if mission.is_started() {
+            reset_button.on_press(MissionMessage::Reset)
+        } else {
+            reset_button
+        };
+
+        Column::new()
+            .push(mono_label_text("Mission", state, Some(color)))
+            .push(
+                Row::new()
+                    .push(PickList::new(
+                        &mut self.countdown_picker,
+                        Cow::Borrowed(Countdown::ALL),
+                        Some(self.countdown),
+                        MissionMessage::SelectCountdown,
+                    ))
+                    .push(start_button)
+                    .push(hold_button)
+                    .push(reset_button)
+                    .spacing(10)
+                    .align_items(Align::Center),
+            )
+            .spacing(5)
+            .align_items(Align::Center)
+            .into()
+    }
+}
+
+/// Time to count down from when the mission clock is started
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Countdown {
+    None,
+    Seconds10,
+    Seconds30,
+    Minutes1,
+    Minutes5,
+    Minutes10,
+}
+
+impl Countdown {
+    pub const ALL: &'static [Countdown] = &[
+        Countdown::None,
+        Countdown::Seconds10,
+        Countdown::Seconds30,
+        Countdown::Minutes1,
+        Countdown::Minutes5,
+        Countdown::Minutes10,
+    ];
+
+    pub const fn duration(self) -> Duration {
+        match self {
+            Countdown::None => Duration::ZERO,
+            Countdown::Seconds10 => Duration::seconds(10),
+            Countdown::Seconds30 => Duration::seconds(30),
+            Countdown::Minutes1 => Duration::minutes(1),
+            Countdown::Minutes5 => Duration::minutes(5),
+            Countdown::Minutes10 => Duration::minutes(10),
+        }
+    }
+}
+
+impl Display for Countdown {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let duration = self.duration();
+
+        if duration.whole_minutes() > 0 {
+            write!(f, "T-{} min", duration.whole_minutes())
+        } else {
+            write!(f, "T-{} s", duration.whole_seconds())
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use iced::{Container, Element, HorizontalAlignment, Length, Space, Text};
+
+use crate::style;
+
+use super::instrument::power::LowVoltage;
+
+/// Alert that the vehicle's battery is running low, on every screen
+pub fn power_alarm<'m, Message: 'm>(alarm: Option<LowVoltage>) -> Element<'m, Message> {
+    let LowVoltage { level, reading } = match alarm {
+        Some(alarm) => alarm,
+        None => return Space::new(Length::Shrink, Length::Shrink).into(),
+    };
+
+    let text = match reading.lowest_cell() {
+        Some(lowest_cell) => format!(
+            "{}: bus {:.2} V, lowest cell {:.3} V, {}% charge",
+            level, reading.bus_voltage, lowest_cell, reading.state_of_charge
+        ),
+        None => format!(
+            "{}: bus {:.2} V, {}% charge",
+            level, reading.bus_voltage, reading.state_of_charge
+        ),
+    };
+
+    Container::new(
+        Text::new(text)
+            .size(24)
+            .horizontal_alignment(HorizontalAlignment::Center)
+            .width(Length::Fill),
+    )
+    .padding(10)
+    .width(Length::Fill)
+    .style(style::Banner(level.color()))
+    .into()
+}
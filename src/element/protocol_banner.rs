@@ -0,0 +1,44 @@
+use iced::{Container, Element, HorizontalAlignment, Length, Space, Text};
+use interlink::proto::PROTOCOL_VERSION;
+
+use crate::{
+    comm::handshake::Compatibility,
+    style::{self, colors},
+};
+
+/// Warn that the vehicle does not speak the same protocol as the station
+pub fn protocol_banner<'m, Message: 'm>(
+    compatibility: Option<Compatibility>,
+) -> Element<'m, Message> {
+    let (text, color) = match compatibility {
+        Some(Compatibility::VersionMismatch { vehicle }) => (
+            format!(
+                "Protocol mismatch: the vehicle speaks version {} but the station speaks version {}. \
+                 Commands and telemetry are disabled.",
+                vehicle, PROTOCOL_VERSION
+            ),
+            colors::ERROR,
+        ),
+        Some(Compatibility::Degraded { missing }) => (
+            format!(
+                "The vehicle does not support {}, which will be unavailable.",
+                missing
+            ),
+            colors::WARNING,
+        ),
+        Some(Compatibility::Full) | None => {
+            return Space::new(Length::Shrink, Length::Shrink).into()
+        }
+    };
+
+    Container::new(
+        Text::new(text)
+            .size(24)
+            .horizontal_alignment(HorizontalAlignment::Center)
+            .width(Length::Fill),
+    )
+    .padding(10)
+    .width(Length::Fill)
+    .style(style::Banner(color))
+    .into()
+}
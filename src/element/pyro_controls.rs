@@ -0,0 +1,212 @@
+use iced::{button, Align, Button, Column, Element, Length, Row, Text};
+use interlink::proto::{Command, PyroChannel};
+use time::Duration;
+use tracing::{info, warn};
+
+use crate::{
+    style::{self, colors},
+    time_manager::unit::LocalTime,
+};
+
+/// How long an armed channel waits for its firing to be confirmed
+const CONFIRM_TIMEOUT: Duration = Duration::seconds(5);
+
+#[derive(Debug, Clone, Copy)]
+pub enum PyroMessage {
+    /// Ready a channel to fire, which must then be confirmed
+    Arm(u8),
+    /// Fire the armed channel
+    Confirm,
+    Cancel,
+}
+
+/// A pyro channel as last reported by the vehicle
+#[derive(Debug)]
+struct Channel {
+    status: PyroChannel,
+    fire_button: button::State,
+}
+
+/// State of the vehicle's pyro channels, with buttons to fire them
+///
+/// Firing a channel takes two presses, one to arm it and another within
+/// `CONFIRM_TIMEOUT` to confirm.
+#[derive(Debug, Default)]
+pub struct PyroControls {
+    channels: Vec<Channel>,
+    /// Channel armed to fire, waiting for confirmation, and when it was armed
+    pending: Option<(u8, LocalTime)>,
+    cancel_button: button::State,
+}
+
+impl PyroControls {
+    /// Update the channels from a status report sent by the vehicle
+    pub fn set_status(&mut self, status: &[PyroChannel]) {
+        for (index, &status) in (0..).zip(status) {
+            match self.channels.get_mut(usize::from(index)) {
+                Some(channel) => {
+                    if status.fired && !channel.status.fired {
+                        info!(channel = %label(index), "Pyro channel fired");
+                    } else if !status.continuity && channel.status.continuity {
+                        warn!(channel = %label(index), "Pyro channel lost continuity");
+                    }
+
+                    channel.status = status;
+                }
+                None => self.channels.push(Channel {
+                    status,
+                    fire_button: button::State::new(),
+                }),
+            }
+        }
+
+        self.channels.truncate(status.len());
+    }
+
+    /// Forget the channels of a vehicle that has disconnected
+    pub fn clear(&mut self) {
+        self.channels.clear();
+        self.pending = None;
+    }
+
+    /// Disarm a channel whose firing was not confirmed in time
+    pub fn poll(&mut self, now: LocalTime) {
+        if let Some((channel, armed_at)) = self.pending {
+            if now.duration_since(&armed_at) >= CONFIRM_TIMEOUT {
+                info!(channel = %label(channel), "Firing not confirmed in time");
+
+                self.pending = None;
+            }
+        }
+    }
+
+    /// Handle a message, returning the command to send to the vehicle if any
+    pub fn update(&mut self, message: PyroMessage, now: LocalTime) -> Option<Command> {
+        match message {
+            PyroMessage::Arm(channel) => {
+                info!(channel = %label(channel), "Pyro channel armed, waiting for confirmation");
+
+                self.pending = Some((channel, now));
+
+                None
+            }
+            PyroMessage::Confirm => {
+                let (channel, armed_at) = self.pending.take()?;
+
+                if now.duration_since(&armed_at) >= CONFIRM_TIMEOUT {
+                    warn!(channel = %label(channel), "Firing confirmed too late, not firing");
+
+                    return None;
+                }
+
+                warn!(channel = %label(channel), time = %now.format(), "Firing pyro channel");
+
+                Some(Command::FirePyro { channel })
+            }
+            PyroMessage::Cancel => {
+                if let Some((channel, _)) = self.pending.take() {
+                    info!(channel = %label(channel), "Firing cancelled");
+                }
+
+                None
+            }
+        }
+    }
+
+    /// Channels can only be fired if `can_fire`, and the vehicle reports them
+    /// as armed and ready
+    pub fn view(&mut self, can_fire: bool) -> Element<'_, PyroMessage> {
+        if self.channels.is_empty() {
+            return Text::new("No pyro channels")
+                .color(colors::SECONDARY_TEXT)
+                .into();
+        }
+
+        let pending = self.pending.map(|(channel, _)| channel);
+
+        let mut column = Column::new().spacing(5).align_items(Align::Center);
+
+        for (index, channel) in (0..).zip(&mut self.channels) {
+            let status = channel.status;
+            let color = colors::pyro_channel(status);
+
+            let state = if status.fired {
+                "FIRED"
+            } else if status.armed {
+                "ARMED"
+            } else {
+                "SAFE"
+            };
+
+            let (continuity, continuity_color) = if status.continuity {
+                ("CONT", colors::TEXT)
+            } else if status.fired {
+                ("OPEN", colors::SECONDARY_TEXT)
+            } else {
+                ("OPEN", colors::WARNING)
+            };
+
+            let (fire_label, message) = if pending == Some(index) {
+                ("Confirm", PyroMessage::Confirm)
+            } else {
+                ("Fire", PyroMessage::Arm(index))
+            };
+
+            let fire_button = Button::new(&mut channel.fire_button, Text::new(fire_label))
+                .style(style::ControlCluster)
+                .width(Length::Units(80));
+            let fire_button = if can_fire && status.armed && status.continuity && !status.fired {
+                fire_button.on_press(message)
+            } else {
+                fire_button
+            };
+
+            column = column.push(
+                Row::new()
+                    .push(
+                        Text::new(label(index))
+                            .font(style::fonts::MONOSPACE)
+                            .color(color),
+                    )
+                    .push(
+                        Text::new(state)
+                            .font(style::fonts::MONOSPACE)
+                            .color(color)
+                            .width(Length::Units(60)),
+                    )
+                    .push(
+                        Text::new(continuity)
+                            .font(style::fonts::MONOSPACE)
+                            .color(continuity_color),
+                    )
+                    .push(fire_button)
+                    .spacing(10)
+                    .align_items(Align::Center),
+            );
+        }
+
+        if let Some(channel) = pending {
+            column = column.push(
+                Row::new()
+                    .push(
+                        Text::new(format!("Confirm to fire {}", label(channel)))
+                            .color(colors::PYRO_ARMED),
+                    )
+                    .push(
+                        Button::new(&mut self.cancel_button, Text::new("Cancel"))
+                            .on_press(PyroMessage::Cancel)
+                            .style(style::ControlCluster),
+                    )
+                    .spacing(10)
+                    .align_items(Align::Center),
+            );
+        }
+
+        column.into()
+    }
+}
+
+/// Name of a channel as shown to the operator, counting from one
+fn label(channel: u8) -> String {
+    format!("Pyro {}", u16::from(channel) + 1)
+}
@@ -0,0 +1,70 @@
+use std::borrow::Cow;
+
+use iced::{
+    button, pick_list, slider, Align, Button, Column, Element, Length, PickList, Row, Slider, Text,
+};
+
+use crate::{
+    session::replay::{ReplayMessage, ReplaySpeed},
+    style,
+    time_manager::{clock::ReplayClock, format_duration},
+};
+
+use super::mono_label_text;
+
+#[derive(Debug, Default)]
+pub struct ReplayControls {
+    pause_button: button::State,
+    speed_picker: pick_list::State<ReplaySpeed>,
+    seek_slider: slider::State,
+}
+
+impl ReplayControls {
+    pub fn view(&mut self, clock: &ReplayClock) -> Element<'_, ReplayMessage> {
+        let elapsed = clock.elapsed();
+        let duration = clock.duration();
+
+        Column::new()
+            .push(mono_label_text(
+                "Replay",
+                format!(
+                    "{} / {}",
+                    format_duration(elapsed),
+                    format_duration(duration)
+                ),
+                Some(style::colors::ACCENT),
+            ))
+            .push(
+                Slider::new(
+                    &mut self.seek_slider,
+                    0.0..=duration.as_seconds_f64(),
+                    elapsed.as_seconds_f64(),
+                    ReplayMessage::Seek,
+                )
+                .step(0.1)
+                .width(Length::Units(300)),
+            )
+            .push(
+                Row::new()
+                    .push(
+                        Button::new(
+                            &mut self.pause_button,
+                            Text::new(if clock.is_paused() { "Play" } else { "Pause" }),
+                        )
+                        .on_press(ReplayMessage::TogglePause)
+                        .style(style::ControlCluster),
+                    )
+                    .push(PickList::new(
+                        &mut self.speed_picker,
+                        Cow::Borrowed(ReplaySpeed::ALL),
+                        ReplaySpeed::from_multiplier(clock.speed()),
+                        ReplayMessage::ChangeSpeed,
+                    ))
+                    .spacing(10)
+                    .align_items(Align::Center),
+            )
+            .spacing(5)
+            .align_items(Align::Center)
+            .into()
+    }
+}
@@ -1,7 +1,11 @@
 use iced::{Align, Column, Element, Length, Space, Text};
-use interlink::{phy::InterlinkMethod, proto::VehicleIdentification};
+use interlink::{
+    phy::InterlinkMethod,
+    proto::{FlightPhase, VehicleIdentification},
+};
 
 use crate::{
+    comm::{sequence::SequenceTracker, stats::LinkStats, LinkStatus},
     element::mono_label_text_tooltip,
     style::{
         self,
@@ -12,16 +16,22 @@ use crate::{
 
 pub fn telemetry_status<'m, Message: 'm>(
     time_manager: &TimeManager,
-    interlink: Option<InterlinkMethod>,
+    link: Option<&LinkStatus>,
     vehicle: Option<&VehicleIdentification>,
+    flight_phase: Option<FlightPhase>,
 ) -> Element<'m, Message> {
     Column::new()
         .push(Space::new(Length::Shrink, Length::Fill))
         .push(Text::new("Telemetry").size(32))
+        .push(flight_phase_text(flight_phase))
         .push(Space::new(Length::Shrink, Length::Units(16)))
         .push(vehicle_id(vehicle))
-        .push(interlink_method(interlink))
+        .push(interlink_method(link.map(|link| link.method)))
+        .push(peer(link.and_then(|link| link.peer.as_deref())))
         .push(time_since_last_packet(time_manager))
+        .push(time_sync(time_manager))
+        .push(link_stats(link.and_then(|link| link.stats)))
+        .push(packet_loss(link.map(|link| &link.sequence)))
         .push(Space::new(Length::Shrink, Length::Fill))
         .width(Length::Fill)
         .height(Length::Fill)
@@ -30,6 +40,18 @@ pub fn telemetry_status<'m, Message: 'm>(
         .into()
 }
 
+fn flight_phase_text<'m, Message: 'm>(flight_phase: Option<FlightPhase>) -> Element<'m, Message> {
+    match flight_phase {
+        Some(flight_phase) => Text::new(flight_phase.to_string().to_uppercase())
+            .size(40)
+            .color(colors::flight_phase(flight_phase)),
+        None => Text::new("NO PHASE")
+            .size(40)
+            .color(style::colors::SECONDARY_TEXT),
+    }
+    .into()
+}
+
 fn interpolate_error_color(progress: f32) -> Color {
     let error_progress = progress.clamp(0.0, 1.0);
     let warn_progress = 1.0 - progress;
@@ -44,14 +66,19 @@ fn interpolate_error_color(progress: f32) -> Color {
     )
 }
 
+/// Color for a delay between packets
+fn delay_color(milliseconds: i128) -> Color {
+    match milliseconds {
+        0..=500 => style::colors::GOOD,
+        i @ 501..=5000 => interpolate_error_color((i - 500) as f32 / 5000.0),
+        _ => style::colors::ERROR,
+    }
+}
+
 fn time_since_last_packet<'m, Message: 'm>(time_manager: &TimeManager) -> Element<'m, Message> {
     let (time_since_last_packet, color) =
         if let Some(time_since_last_packet) = time_manager.duration_since_last_packet() {
-            let color = match time_since_last_packet.whole_milliseconds() {
-                0..=500 => style::colors::GOOD,
-                i @ 501..=5000 => interpolate_error_color((i - 500) as f32 / 5000.0),
-                _ => style::colors::ERROR,
-            };
+            let color = delay_color(time_since_last_packet.whole_milliseconds());
 
             (format_duration(time_since_last_packet), color)
         } else {
@@ -66,9 +93,56 @@ fn time_since_last_packet<'m, Message: 'm>(time_manager: &TimeManager) -> Elemen
     )
 }
 
+fn time_sync<'m, Message: 'm>(time_manager: &TimeManager) -> Element<'m, Message> {
+    let latency = match time_manager.last_sync() {
+        Some(sync) => mono_label_text_tooltip(
+            "RTT",
+            format!("{:.1} ms", sync.round_trip.as_seconds_f64() * 1000.0),
+            "Round Trip Time of the Latest Time Sync",
+            Some(delay_color(sync.round_trip.whole_milliseconds())),
+        ),
+        None => mono_label_text_tooltip(
+            "RTT",
+            "--.- ms",
+            "Round Trip Time of the Latest Time Sync",
+            Some(style::colors::SECONDARY_TEXT),
+        ),
+    };
+
+    // The vehicle's clock is known to within half the round trip of the
+    // exchange its offset was taken from
+    let quality = match time_manager
+        .clock_model()
+        .fit()
+        .and_then(|fit| fit.round_trip)
+    {
+        Some(round_trip) => mono_label_text_tooltip(
+            "Sync",
+            format!("±{:.1} ms", round_trip.as_seconds_f64() * 500.0),
+            "Uncertainty of the Vehicle's Clock Offset",
+            Some(delay_color(round_trip.whole_milliseconds())),
+        ),
+        None => mono_label_text_tooltip(
+            "Sync",
+            "None",
+            "Uncertainty of the Vehicle's Clock Offset",
+            Some(style::colors::SECONDARY_TEXT),
+        ),
+    };
+
+    Column::new()
+        .push(latency)
+        .push(quality)
+        .align_items(Align::Center)
+        .spacing(2)
+        .into()
+}
+
 fn interlink_method<'m, Message: 'm>(interlink: Option<InterlinkMethod>) -> Element<'m, Message> {
     let (interlink, color) = match interlink {
         Some(InterlinkMethod::Serial) => ("Serial", style::colors::ACTIVE),
+        Some(InterlinkMethod::Udp) => ("UDP", style::colors::ACTIVE),
+        Some(InterlinkMethod::Tcp) => ("TCP", style::colors::ACTIVE),
         None => ("None", style::colors::SECONDARY_TEXT),
     };
 
@@ -80,14 +154,6 @@ fn interlink_method<'m, Message: 'm>(interlink: Option<InterlinkMethod>) -> Elem
     //         None,
     // )
 
-    // TODO:
-    // mono_label_text_tooltip(
-    //     "Data Rate",
-    //     "???",
-    //     "Packets Received per Second",
-    //     Some(style::colors::WARNING),
-    // )
-
     // TODO: other information?
     mono_label_text_tooltip(
         "Interlink",
@@ -97,6 +163,113 @@ fn interlink_method<'m, Message: 'm>(interlink: Option<InterlinkMethod>) -> Elem
     )
 }
 
+fn link_stats<'m, Message: 'm>(stats: Option<LinkStats>) -> Element<'m, Message> {
+    let stats = match stats {
+        Some(stats) => stats,
+        None => {
+            return mono_label_text_tooltip(
+                "Data Rate",
+                "--.- pkt/s",
+                "Packets Received per Second",
+                Some(style::colors::SECONDARY_TEXT),
+            )
+        }
+    };
+
+    // Held to the same standard as the time since last packet, using the mean delay between packets
+    let rate_color = if stats.packets_per_second > 0.0 {
+        delay_color((1000.0 / stats.packets_per_second) as i128)
+    } else {
+        style::colors::ERROR
+    };
+
+    let error_color = if stats.error_rate == 0 {
+        style::colors::GOOD
+    } else {
+        interpolate_error_color(f32::from(stats.error_rate) / 100.0)
+    };
+
+    let gap_color = if stats.gaps == 0 {
+        style::colors::GOOD
+    } else {
+        style::colors::WARNING
+    };
+
+    let throughput = if stats.bytes_per_second >= 1000.0 {
+        format!("{:.1} kB/s", stats.bytes_per_second / 1000.0)
+    } else {
+        format!("{:.0} B/s", stats.bytes_per_second)
+    };
+
+    Column::new()
+        .push(mono_label_text_tooltip(
+            "Data Rate",
+            format!("{:.1} pkt/s", stats.packets_per_second),
+            "Packets Received per Second",
+            Some(rate_color),
+        ))
+        .push(mono_label_text_tooltip(
+            "Throughput",
+            throughput,
+            "Bytes Received per Second",
+            Some(rate_color),
+        ))
+        .push(mono_label_text_tooltip(
+            "Errors",
+            format!("{} DEC {} CRC", stats.decode_errors, stats.crc_failures),
+            "Frames That Failed to Decode / Failed Their CRC",
+            Some(error_color),
+        ))
+        .push(mono_label_text_tooltip(
+            "Gaps",
+            stats.gaps.to_string(),
+            "Periods Without Packets",
+            Some(gap_color),
+        ))
+        .align_items(Align::Center)
+        .spacing(2)
+        .into()
+}
+
+fn packet_loss<'m, Message: 'm>(sequence: Option<&SequenceTracker>) -> Element<'m, Message> {
+    let sequence = match sequence {
+        Some(sequence) => sequence,
+        None => return Space::new(Length::Shrink, Length::Shrink).into(),
+    };
+
+    let loss = sequence.loss_percentage();
+
+    let color = if loss > 0.0 {
+        interpolate_error_color(loss / 100.0)
+    } else if sequence.duplicates > 0 || sequence.out_of_order > 0 {
+        style::colors::WARNING
+    } else {
+        style::colors::GOOD
+    };
+
+    mono_label_text_tooltip(
+        "Loss",
+        format!(
+            "{:.1}% {} DUP {} OOO",
+            loss, sequence.duplicates, sequence.out_of_order
+        ),
+        "Packets Lost / Duplicated / Out of Order",
+        Some(color),
+    )
+}
+
+fn peer<'m, Message: 'm>(peer: Option<&str>) -> Element<'m, Message> {
+    match peer {
+        Some(peer) => mono_label_text_tooltip(
+            "Peer",
+            peer,
+            "Other End of the Interlink",
+            Some(colors::SECONDARY_TEXT),
+        ),
+        None => Space::new(Length::Shrink, Length::Shrink).into(),
+    }
+}
+
 fn vehicle_id<'m, Message: 'm>(vehicle: Option<&VehicleIdentification>) -> Element<'m, Message> {
     match vehicle {
         Some(vehicle_id) => mono_label_text_tooltip(
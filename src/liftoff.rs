@@ -0,0 +1,123 @@
+//! Detection of the moment the vehicle leaves the pad, from its accelerometer
+//! or from the flight phase it reports
+
+use std::fmt::{self, Display, Formatter};
+
+use interlink::proto::{FlightPhase, Vector3};
+use time::Duration;
+
+use crate::{time_manager::unit::VehicleTime, util::env_or};
+
+/// Acceleration in g that counts as liftoff when `LIFTOFF_ACCELERATION` is not set
+const DEFAULT_ACCELERATION: f64 = 2.0;
+/// How long in ms the acceleration must last when `LIFTOFF_DURATION_MS` is not set
+const DEFAULT_DURATION_MS: u32 = 100;
+
+/// What gave away that the vehicle had lifted off
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LiftoffSource {
+    Acceleration,
+    FlightPhase,
+}
+
+impl Display for LiftoffSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            LiftoffSource::Acceleration => write!(f, "acceleration"),
+            LiftoffSource::FlightPhase => write!(f, "flight phase"),
+        }
+    }
+}
+
+/// The moment the vehicle lifted off, by its own clock
+#[derive(Debug, Clone, Copy)]
+pub struct Liftoff {
+    pub time: VehicleTime,
+    pub source: LiftoffSource,
+}
+
+/// Watches for the vehicle leaving the pad
+///
+/// Liftoff is only detected once, until the detector is reset.
+#[derive(Debug)]
+pub struct LiftoffDetector {
+    /// Magnitude of acceleration in mg that counts as liftoff
+    threshold: f64,
+    /// How long the acceleration must stay above the threshold
+    duration: Duration,
+
+    /// First reading of the current run above the threshold
+    above_since: Option<VehicleTime>,
+    liftoff: Option<Liftoff>,
+}
+
+impl LiftoffDetector {
+    /// Create a detector configured by the `LIFTOFF_ACCELERATION` and
+    /// `LIFTOFF_DURATION_MS` environment variables
+    pub fn from_env() -> Self {
+        Self {
+            threshold: env_or("LIFTOFF_ACCELERATION", DEFAULT_ACCELERATION) * 1000.0,
+            duration: Duration::milliseconds(
+                env_or("LIFTOFF_DURATION_MS", DEFAULT_DURATION_MS).into(),
+            ),
+
+            above_since: None,
+            liftoff: None,
+        }
+    }
+
+    /// Look for sustained acceleration in a reading in mg, returning when it
+    /// started the first time it lasts long enough
+    pub fn accelerometer(&mut self, time: VehicleTime, raw: Vector3<i32>) -> Option<Liftoff> {
+        if self.liftoff.is_some() {
+            return None;
+        }
+
+        let (x, y, z) = (raw.x as f64, raw.y as f64, raw.z as f64);
+
+        if (x * x + y * y + z * z).sqrt() < self.threshold {
+            self.above_since = None;
+
+            return None;
+        }
+
+        let since = *self.above_since.get_or_insert(time);
+
+        if time.as_duration() - since.as_duration() < self.duration {
+            return None;
+        }
+
+        self.detected(since, LiftoffSource::Acceleration)
+    }
+
+    /// Take the vehicle going from the pad to boost as liftoff, returning when
+    /// it happened if it had not been detected already
+    pub fn flight_phase(
+        &mut self,
+        time: VehicleTime,
+        previous: Option<FlightPhase>,
+        phase: FlightPhase,
+    ) -> Option<Liftoff> {
+        // Boost first heard without the pad before it may have started long ago
+        let from_pad = matches!(previous, Some(FlightPhase::Idle | FlightPhase::Armed));
+
+        if self.liftoff.is_some() || !from_pad || phase != FlightPhase::Boost {
+            return None;
+        }
+
+        self.detected(time, LiftoffSource::FlightPhase)
+    }
+
+    /// Watch for another liftoff
+    pub const fn reset(&mut self) {
+        self.above_since = None;
+        self.liftoff = None;
+    }
+
+    const fn detected(&mut self, time: VehicleTime, source: LiftoffSource) -> Option<Liftoff> {
+        let liftoff = Liftoff { time, source };
+        self.liftoff = Some(liftoff);
+
+        Some(liftoff)
+    }
+}
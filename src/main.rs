@@ -2,35 +2,74 @@
 #![deny(clippy::unwrap_used, clippy::trivially_copy_pass_by_ref)]
 #![warn(clippy::unwrap_in_result, clippy::missing_const_for_fn)]
 
-use std::time::Duration;
-
-use comm::serial::{SerialEvent, SerialSubscription};
-use element::instrument::{
-    data_view::{Accelerometer, DataView, Magnetometer, Temperature},
-    time_series::TimeSeriesInstrument,
-    vector::VectorInstrument,
-    InstrumentMessage,
+use std::{env, path::Path, process, time::Duration};
+
+use attitude::AttitudeEstimator;
+use comm::{
+    command::CommandTracker,
+    handshake::{Compatibility, STATION_PROTOCOL},
+    sequence::{Arrival, SequenceTracker},
+    start_transport,
+    time_sync::{self, TimeSync},
+    LinkStatus, SerialEvent, TransportSubscription,
+};
+use element::{
+    command_controls::{CommandControls, CommandMessage},
+    instrument::{
+        altimeter::AltimeterInstrument,
+        attitude::AttitudeInstrument,
+        data_view::{
+            Accelerometer, Altitude, DataView, Magnetometer, Position, Power, Temperature,
+        },
+        position::PositionInstrument,
+        power::PowerInstrument,
+        thermometer::ThermometerInstrument,
+        time_series::TimeSeriesInstrument,
+        vector::VectorInstrument,
+        InstrumentMessage,
+    },
+    mission_controls::{MissionControls, MissionMessage},
+    power_alarm::power_alarm,
+    pyro_controls::{PyroControls, PyroMessage},
+    replay_controls::ReplayControls,
 };
 use iced::{
     button, executor,
     keyboard::{self, KeyCode, Modifiers},
     pick_list,
     window::{self, Mode},
-    Application, Clipboard, Color, Command, Container, Element, Length, Settings, Subscription,
+    Application, Clipboard, Color, Column, Command, Container, Element, Length, Settings,
+    Subscription,
 };
 use iced_native::{event, subscription, Event};
 use insomnia::Lock;
-use interlink::{
-    phy::InterlinkMethod,
-    proto::{PacketDown, PacketDownData, VehicleIdentification},
+use interlink::proto::{
+    Capabilities, Command as VehicleCommand, FixType, FlightPhase, PacketDownData, PacketUp,
+    VehicleIdentification,
+};
+use liftoff::{Liftoff, LiftoffDetector};
+use session::{
+    recorder::{start_recording, SessionRecorder},
+    replay::{ReplayMessage, ReplaySubscription},
+    Session, SessionEvent,
 };
-use time_manager::{base::TimeBase, unit::VehicleTime, TimeManager};
+use time_manager::{
+    base::TimeBase,
+    clock::Clock,
+    format_duration,
+    unit::{LocalTime, VehicleTime},
+    TimeManager,
+};
+use tracing::{error, info, trace, warn};
 use tracing_subscriber::EnvFilter;
 use util::inhibit_sleep;
-use view::{acceleration, default, magnetic_field};
+use view::{acceleration, altitude, default, magnetic_field, position, power, temperature};
 
+mod attitude;
 mod comm;
 mod element;
+mod liftoff;
+mod session;
 mod style;
 mod time_manager;
 mod util;
@@ -43,9 +82,19 @@ pub fn main() -> iced::Result {
         .with_env_filter(EnvFilter::from_default_env())
         .init();
 
+    // Replay a recorded session instead of listening for a vehicle if one is given
+    let session = env::args_os().nth(1).map(|path| {
+        Session::open(&path).unwrap_or_else(|error| {
+            error!(%error, path = %Path::new(&path).display(), "Unable to open session");
+
+            process::exit(1)
+        })
+    });
+
     InstrumentCluster::run(Settings {
         antialiasing: true,
         exit_on_close_request: true,
+        flags: session,
         ..Default::default()
     })
 }
@@ -59,6 +108,65 @@ struct Instruments {
     acceleration_vector: VectorInstrument<Accelerometer>,
 
     temperature: TimeSeriesInstrument<Temperature>,
+    thermometer: ThermometerInstrument,
+
+    position: PositionInstrument,
+    position_time: TimeSeriesInstrument<Position>,
+
+    altimeter: AltimeterInstrument,
+    altitude_time: TimeSeriesInstrument<Altitude>,
+
+    attitude: AttitudeInstrument,
+
+    power: PowerInstrument,
+    power_time: TimeSeriesInstrument<Power>,
+}
+
+impl Instruments {
+    /// Create empty instruments, with warning thresholds taken from the environment
+    fn from_env() -> Self {
+        Self {
+            magnetic_field_time: TimeSeriesInstrument::new(5.0),
+            magnetic_field_vector: VectorInstrument::new(),
+
+            acceleration_time: TimeSeriesInstrument::new(5.0),
+            acceleration_vector: VectorInstrument::new(),
+
+            temperature: TimeSeriesInstrument::new(5.0),
+            thermometer: ThermometerInstrument::from_env(),
+
+            position: PositionInstrument::new(),
+            position_time: TimeSeriesInstrument::new(30.0),
+
+            altimeter: AltimeterInstrument::new(),
+            altitude_time: TimeSeriesInstrument::new(30.0),
+
+            attitude: AttitudeInstrument::new(),
+
+            power: PowerInstrument::from_env(),
+            power_time: TimeSeriesInstrument::new(60.0),
+        }
+    }
+
+    /// Let each time series break where packets were lost, if its own readings were among them
+    const fn mark_gap(&mut self) {
+        self.magnetic_field_time.mark_gap();
+        self.acceleration_time.mark_gap();
+        self.temperature.mark_gap();
+        self.position_time.mark_gap();
+        self.altitude_time.mark_gap();
+        self.power_time.mark_gap();
+    }
+
+    /// Shade the time series from `time` onwards for a new phase of flight
+    fn mark_phase(&mut self, time: VehicleTime, phase: FlightPhase) {
+        self.magnetic_field_time.mark_phase(time, phase);
+        self.acceleration_time.mark_phase(time, phase);
+        self.temperature.mark_phase(time, phase);
+        self.position_time.mark_phase(time, phase);
+        self.altitude_time.mark_phase(time, phase);
+        self.power_time.mark_phase(time, phase);
+    }
 }
 
 pub struct InstrumentCluster {
@@ -71,15 +179,30 @@ pub struct InstrumentCluster {
     time_base: TimeBase,
 
     instruments: Instruments,
+    attitude: AttitudeEstimator,
+    liftoff: LiftoffDetector,
     data_view: Option<DataView>,
 
-    serial: SerialSubscription,
-    interlink: Option<InterlinkMethod>,
+    transport: Option<TransportSubscription>,
+    replay: Option<ReplaySubscription>,
+    link: Option<LinkStatus>,
     vehicle: Option<VehicleIdentification>,
+    flight_phase: Option<FlightPhase>,
+    /// Kept after the vehicle disconnects, so a mismatch stays on screen
+    compatibility: Option<Compatibility>,
+
+    recorder: Option<SessionRecorder>,
+
+    commands: CommandTracker,
+    time_sync: TimeSync,
 
     time_base_picker: pick_list::State<TimeBase>,
     quit_button: button::State,
     fullscreen_button: button::State,
+    replay_controls: ReplayControls,
+    command_controls: CommandControls,
+    pyro_controls: PyroControls,
+    mission_controls: MissionControls,
 
     #[allow(dead_code)]
     sleep_lock: Option<Box<dyn Lock>>,
@@ -95,14 +218,32 @@ pub enum Message {
     SerialEvent(SerialEvent),
     ChangeTimeBase(TimeBase),
     Instrument(InstrumentMessage),
+    Replay(ReplayMessage),
+    Command(CommandMessage),
+    Pyro(PyroMessage),
+    Mission(MissionMessage),
 }
 
 impl Application for InstrumentCluster {
     type Executor = executor::Default;
     type Message = Message;
-    type Flags = ();
+    type Flags = Option<Session>;
+
+    fn new(session: Option<Session>) -> (Self, Command<Self::Message>) {
+        let (transport, replay, recorder) = match session {
+            Some(session) => (None, Some(ReplaySubscription::start(session)), None),
+            None => (
+                Some(start_transport(Duration::from_secs(1))),
+                None,
+                start_recording(),
+            ),
+        };
+
+        let clock = match &replay {
+            Some(replay) => Clock::Replay(replay.clock().clone()),
+            None => Clock::Local,
+        };
 
-    fn new(_flags: ()) -> (Self, Command<Self::Message>) {
         (
             Self {
                 quit: false,
@@ -110,27 +251,33 @@ impl Application for InstrumentCluster {
                 window_mode: Mode::Windowed,
                 window_size: (0, 0),
 
-                instruments: Instruments {
-                    magnetic_field_time: TimeSeriesInstrument::new(5.0),
-                    magnetic_field_vector: VectorInstrument::new(),
-
-                    acceleration_time: TimeSeriesInstrument::new(5.0),
-                    acceleration_vector: VectorInstrument::new(),
-
-                    temperature: TimeSeriesInstrument::new(5.0),
-                },
+                instruments: Instruments::from_env(),
+                attitude: AttitudeEstimator::default(),
+                liftoff: LiftoffDetector::from_env(),
                 data_view: None,
 
-                time: TimeManager::setup(),
+                time: TimeManager::setup(clock),
                 time_base: TimeBase::GroundControl,
 
-                serial: SerialSubscription::start(Duration::from_secs(1)),
-                interlink: None,
+                transport,
+                replay,
+                link: None,
                 vehicle: None,
+                flight_phase: None,
+                compatibility: None,
+
+                recorder,
+
+                commands: CommandTracker::from_env(),
+                time_sync: TimeSync::from_env(),
 
                 time_base_picker: pick_list::State::default(),
                 quit_button: button::State::default(),
                 fullscreen_button: button::State::default(),
+                replay_controls: ReplayControls::default(),
+                command_controls: CommandControls::default(),
+                pyro_controls: PyroControls::default(),
+                mission_controls: MissionControls::default(),
 
                 sleep_lock: inhibit_sleep(),
             },
@@ -139,7 +286,11 @@ impl Application for InstrumentCluster {
     }
 
     fn title(&self) -> String {
-        String::from("Ground Station")
+        if self.replay.is_some() {
+            String::from("Ground Station (Replay)")
+        } else {
+            String::from("Ground Station")
+        }
     }
 
     fn background_color(&self) -> Color {
@@ -164,41 +315,214 @@ impl Application for InstrumentCluster {
             }
             Message::WindowFocusChange { focused } => self.window_focused = focused,
             Message::WindowSizeChange { width, height } => self.window_size = (width, height),
-            Message::Refresh => { /* TODO: replace with something better? */ }
-            Message::SerialEvent(SerialEvent::PacketReceived(PacketDown { time, data })) => {
-                let time = VehicleTime::from_packet(time);
+            Message::Refresh => {
+                /* TODO: replace with something better? */
+                for packet in self.commands.poll(self.time.now()) {
+                    self.send(packet);
+                }
+
+                self.pyro_controls.poll(self.time.now());
+
+                if self.can_sync_time() {
+                    if let Some(packet) = self.time_sync.poll(self.time.now()) {
+                        self.send(packet);
+                    }
+                }
+            }
+            Message::SerialEvent(SerialEvent::PacketReceived(packet)) => {
+                let time = VehicleTime::from_packet(packet.time, self.time.now());
+
+                self.record(
+                    time.received(),
+                    SessionEvent::PacketReceived(packet.clone()),
+                );
+
+                let arrival = self.link.as_mut().map_or(Arrival::InOrder, |link| {
+                    // The vehicle numbers its packets from zero again when it reboots
+                    if let PacketDownData::Hello(_) = packet.data {
+                        link.sequence.restart(packet.sequence)
+                    } else {
+                        link.sequence.packet_received(packet.sequence)
+                    }
+                });
+
+                match arrival {
+                    Arrival::Duplicate => {
+                        trace!(sequence = packet.sequence, "Ignoring duplicate packet");
+
+                        return Command::none();
+                    }
+                    Arrival::AfterLoss(lost) => {
+                        warn!(lost, sequence = packet.sequence, "Packets lost");
+
+                        self.instruments.mark_gap();
+                    }
+                    Arrival::OutOfOrder => {
+                        // A late reading would rewind the instruments to when it was
+                        // taken, but a late response still answers its request
+                        if !is_response(&packet.data) {
+                            trace!(sequence = packet.sequence, "Ignoring out of order packet");
+
+                            return Command::none();
+                        }
+                    }
+                    Arrival::InOrder => {}
+                }
+
+                // A late packet says nothing new about the vehicle's clock
+                if matches!(arrival, Arrival::InOrder | Arrival::AfterLoss(_)) {
+                    self.time.packet_received(time);
+                }
 
-                self.time.packet_received(time);
+                // Any other packet may have decoded into something the vehicle never
+                // meant, only a new hello can tell whether the vehicle now matches
+                if !self.understands_vehicle() && !matches!(packet.data, PacketDownData::Hello(_)) {
+                    trace!(
+                        sequence = packet.sequence,
+                        "Ignoring packet from mismatched vehicle"
+                    );
 
-                match data {
+                    return Command::none();
+                }
+
+                match packet.data {
                     PacketDownData::Magnetometer(reading) => {
                         self.instruments
                             .magnetic_field_time
                             .add_reading(time, reading);
                         self.instruments.magnetic_field_vector.set_reading(reading);
+                        self.attitude.magnetometer(reading);
                     }
                     PacketDownData::Accelerometer(reading) => {
                         self.instruments
                             .acceleration_time
                             .add_reading(time, reading);
                         self.instruments.acceleration_vector.set_reading(reading);
+                        self.attitude.accelerometer(reading);
+
+                        if let Some(liftoff) = self.liftoff.accelerometer(time, reading) {
+                            self.lifted_off(liftoff);
+                        }
                     }
                     PacketDownData::ECompassTemperature(temperature) => {
-                        self.instruments.temperature.add_reading(time, temperature)
+                        self.instruments.temperature.add_reading(time, temperature);
+                        self.instruments.thermometer.add_reading(temperature);
+                    }
+                    PacketDownData::Gnss(fix) => {
+                        self.instruments.position.add_reading(fix);
+
+                        // Without a fix the rest of its fields are meaningless
+                        if fix.fix != FixType::NoFix {
+                            self.instruments.position_time.add_reading(time, fix);
+                        }
+                    }
+                    PacketDownData::Gyroscope(reading) => {
+                        self.attitude.gyroscope(time, reading);
+
+                        if let Some(orientation) = self.attitude.orientation() {
+                            self.instruments.attitude.set_reading(orientation);
+                        }
+                    }
+                    PacketDownData::FlightPhase(phase) => {
+                        if self.flight_phase != Some(phase) {
+                            info!(%phase, "Flight phase changed");
+
+                            if let Some(liftoff) =
+                                self.liftoff.flight_phase(time, self.flight_phase, phase)
+                            {
+                                self.lifted_off(liftoff);
+                            }
+
+                            self.flight_phase = Some(phase);
+                            self.instruments.mark_phase(time, phase);
+                        }
+                    }
+                    PacketDownData::Pyro(channels) => self.pyro_controls.set_status(&channels),
+                    PacketDownData::Power(reading) => {
+                        self.instruments.power.add_reading(reading.clone());
+                        self.instruments.power_time.add_reading(time, reading);
+                    }
+                    PacketDownData::Barometer(reading) => {
+                        if let Some(altitude) =
+                            self.instruments.altimeter.add_reading(time, reading)
+                        {
+                            self.instruments.altitude_time.add_reading(time, altitude);
+                        }
+                    }
+                    PacketDownData::TimeSync {
+                        station_time,
+                        received,
+                        transmitted,
+                    } => {
+                        if let Some(sync) = time_sync::exchange(
+                            station_time,
+                            VehicleTime::from_packet(received, time.received()),
+                            VehicleTime::from_packet(transmitted, time.received()),
+                            time.received(),
+                        ) {
+                            self.time.time_synced(sync);
+                        }
                     }
                     PacketDownData::Hello(vehicle_identification) => {
+                        // The vehicle may have rebooted, starting its clock again
+                        self.time.reset_clock_model();
+
+                        self.compatibility =
+                            Some(Compatibility::negotiate(vehicle_identification.protocol));
                         self.vehicle.replace(vehicle_identification);
                     }
+                    PacketDownData::Ack(_)
+                    | PacketDownData::Nak { .. }
+                    | PacketDownData::Pong { .. } => self
+                        .commands
+                        .response_received(&packet.data, time.received()),
                 }
             }
-            Message::SerialEvent(SerialEvent::Connected) => {
-                self.interlink = Some(InterlinkMethod::Serial);
+            Message::SerialEvent(SerialEvent::Connected { method, peer }) => {
+                self.record(self.time.now(), SessionEvent::Connected(method));
+
+                self.link = Some(LinkStatus {
+                    method,
+                    peer,
+                    stats: None,
+                    sequence: SequenceTracker::default(),
+                });
+
+                // Only a live vehicle can be greeted, not a replayed one
+                if self.transport.is_some() {
+                    self.send(PacketUp::Welcome(STATION_PROTOCOL));
+                }
             }
-            Message::SerialEvent(SerialEvent::Disconnected) => {
-                if self.interlink == Some(InterlinkMethod::Serial) {
-                    self.interlink.take();
+            Message::SerialEvent(SerialEvent::SendFailed { packet, reason }) => {
+                error!(?packet, %reason, "Failed to send packet to vehicle");
+
+                self.commands.send_failed(&packet, &reason);
+            }
+            Message::SerialEvent(SerialEvent::Stats(stats)) => {
+                if let Some(link) = &mut self.link {
+                    link.stats = Some(stats);
                 }
+            }
+            Message::SerialEvent(SerialEvent::Disconnected) => {
+                self.record(self.time.now(), SessionEvent::Disconnected);
+
+                self.link.take();
                 self.vehicle.take();
+                self.flight_phase.take();
+                self.pyro_controls.clear();
+                self.time_sync.reset();
+                self.time.reset_clock_model();
+            }
+            Message::SerialEvent(SerialEvent::Seeked { pad_readings }) => {
+                // Start over from where the replay now is, as if the session
+                // had been played back up to it
+                self.instruments = Instruments::from_env();
+                for reading in pad_readings {
+                    self.instruments.altimeter.add_pad_reading(reading);
+                }
+                self.attitude = AttitudeEstimator::default();
+                self.liftoff.reset();
+                self.time.reset_mission();
             }
             Message::ChangeTimeBase(time_base) => self.time_base = time_base,
             Message::Instrument(InstrumentMessage::Selected(data_view)) => {
@@ -208,6 +532,30 @@ impl Application for InstrumentCluster {
                     self.data_view.replace(data_view);
                 }
             }
+            Message::Replay(message) => {
+                if let Some(replay) = &self.replay {
+                    replay.update(message);
+                }
+            }
+            Message::Command(message) => {
+                if let Some(command) = self.command_controls.update(message) {
+                    self.send_command(command);
+                }
+            }
+            Message::Mission(message) => {
+                // Let the next flight start the clock again, measured from its own pad
+                if let MissionMessage::Reset = message {
+                    self.liftoff.reset();
+                    self.instruments.altimeter.reset();
+                }
+
+                self.mission_controls.update(message, &mut self.time);
+            }
+            Message::Pyro(message) => {
+                if let Some(command) = self.pyro_controls.update(message, self.time.now()) {
+                    self.send_command(command);
+                }
+            }
         }
 
         Command::none()
@@ -215,7 +563,14 @@ impl Application for InstrumentCluster {
 
     fn subscription(&self) -> Subscription<Self::Message> {
         Subscription::batch([
-            self.serial.subscription().map(Message::SerialEvent),
+            self.transport
+                .as_ref()
+                .map_or_else(Subscription::none, TransportSubscription::subscription)
+                .map(Message::SerialEvent),
+            self.replay
+                .as_ref()
+                .map_or_else(Subscription::none, ReplaySubscription::subscription)
+                .map(Message::SerialEvent),
             // TODO: update differently
             iced::time::every(Duration::from_millis(50)).map(|_| Message::Refresh),
             subscription::events_with(|event, status| match (event, status) {
@@ -247,12 +602,27 @@ impl Application for InstrumentCluster {
     }
 
     fn view(&mut self) -> Element<Self::Message> {
-        Container::new(match self.data_view {
+        let power_alarm = power_alarm(self.instruments.power.alarm());
+
+        let view = match self.data_view {
             None => default::view(self),
             Some(DataView::Accelerometer) => acceleration::view(self),
             Some(DataView::Magnetometer) => magnetic_field::view(self),
-            Some(DataView::Temperature) => todo!(),
-        })
+            Some(DataView::Temperature) => temperature::view(self),
+            Some(DataView::Position) => position::view(self),
+            Some(DataView::Altitude) => altitude::view(self),
+            Some(DataView::Attitude) => view::attitude::view(self),
+            Some(DataView::Power) => power::view(self),
+        };
+
+        Container::new(
+            Column::new()
+                .push(power_alarm)
+                .push(view)
+                .spacing(10)
+                .width(Length::Fill)
+                .height(Length::Fill),
+        )
         .width(Length::Fill)
         .height(Length::Fill)
         .style(style::Window)
@@ -268,3 +638,93 @@ impl Application for InstrumentCluster {
         self.quit
     }
 }
+
+impl InstrumentCluster {
+    fn send(&self, packet: PacketUp) {
+        match &self.transport {
+            Some(transport) => transport.uplink().send(packet),
+            None => warn!(?packet, "Unable to send packets to a replayed vehicle"),
+        }
+    }
+
+    /// Whether packets from the vehicle mean what the station thinks they do
+    const fn understands_vehicle(&self) -> bool {
+        !matches!(
+            self.compatibility,
+            Some(Compatibility::VersionMismatch { .. })
+        )
+    }
+
+    /// Whether the connected vehicle can be sent commands
+    const fn can_command(&self) -> bool {
+        match self.compatibility {
+            Some(compatibility) if self.vehicle.is_some() => {
+                compatibility.supports(Capabilities::COMMANDS)
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether the connected vehicle's pyro channels can be fired
+    const fn can_fire(&self) -> bool {
+        match self.compatibility {
+            Some(compatibility) => self.can_command() && compatibility.supports(Capabilities::PYRO),
+            None => false,
+        }
+    }
+
+    /// Whether the connected vehicle can be asked for its clock
+    const fn can_sync_time(&self) -> bool {
+        match self.compatibility {
+            Some(compatibility) if self.vehicle.is_some() && self.transport.is_some() => {
+                compatibility.supports(Capabilities::TIME_SYNC)
+            }
+            _ => false,
+        }
+    }
+
+    fn send_command(&mut self, command: VehicleCommand) {
+        if !self.can_command() {
+            warn!(?command, "Vehicle cannot be commanded");
+
+            return;
+        }
+
+        let packet = self.commands.send(command, self.time.now());
+
+        self.send(packet);
+    }
+
+    /// Start mission time from the moment the vehicle lifted off
+    fn lifted_off(&mut self, liftoff: Liftoff) {
+        let at = self.time.vehicle_to_local(liftoff.time);
+
+        info!(
+            source = %liftoff.source,
+            at = %at.format(),
+            // How far a manually started countdown was from the real liftoff
+            mission_time = ?self.time.mission().at(at).map(format_duration),
+            "Liftoff detected"
+        );
+
+        self.time.liftoff(liftoff.time);
+    }
+
+    fn record(&self, received: LocalTime, event: SessionEvent) {
+        if let Some(recorder) = &self.recorder {
+            recorder.record(received, event);
+        }
+    }
+}
+
+/// Whether a packet answers a request from the station, rather than reporting
+/// the state of the vehicle
+const fn is_response(data: &PacketDownData) -> bool {
+    matches!(
+        data,
+        PacketDownData::Ack(_)
+            | PacketDownData::Nak { .. }
+            | PacketDownData::Pong { .. }
+            | PacketDownData::TimeSync { .. }
+    )
+}
@@ -0,0 +1,156 @@
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    fs, io,
+    path::Path,
+};
+
+use interlink::{
+    phy::{self, InterlinkMethod},
+    proto::PacketDown,
+};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::time_manager::unit::LocalTime;
+
+pub mod recorder;
+pub mod replay;
+
+/// Bytes identifying a file as a ground station session log.
+pub const SESSION_MAGIC: [u8; 8] = *b"GSESSION";
+
+/// Version of the session file layout.
+///
+/// Must be bumped whenever [`SessionRecord`] or any of the protocol types it
+/// contains change their serialized representation.
+pub const SESSION_VERSION: u16 = 4;
+
+/// File extension used for session logs.
+pub const SESSION_EXTENSION: &str = "session";
+
+/// First frame of every session file.
+///
+/// A session file is a sequence of COBS encoded postcard frames, the first of
+/// which is a `SessionHeader` followed by any number of [`SessionRecord`]s.
+/// Because every frame is self delimiting, a file that was cut short can still
+/// be read up until the last complete frame.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SessionHeader {
+    pub magic: [u8; 8],
+    pub version: u16,
+    pub started: LocalTime,
+}
+
+impl SessionHeader {
+    pub const fn new(started: LocalTime) -> Self {
+        Self {
+            magic: SESSION_MAGIC,
+            version: SESSION_VERSION,
+            started,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SessionRecord {
+    /// When the ground station received the event.
+    pub received: LocalTime,
+    pub event: SessionEvent,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum SessionEvent {
+    PacketReceived(PacketDown),
+    Connected(InterlinkMethod),
+    Disconnected,
+}
+
+/// A session file loaded into memory.
+#[derive(Debug, Clone)]
+pub struct Session {
+    pub header: SessionHeader,
+    pub records: Vec<SessionRecord>,
+}
+
+impl Session {
+    /// Read a session file
+    ///
+    /// Reading stops at the first frame that cannot be decoded, which keeps
+    /// files that were cut short by a crash usable.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, SessionError> {
+        let mut data = fs::read(path)?;
+        let mut frames = data.split_mut(|&byte| byte == phy::serial::COBS_SENTINEL);
+
+        let header = frames
+            .next()
+            .and_then(|frame| postcard::from_bytes_cobs::<SessionHeader>(frame).ok())
+            .filter(|header| header.magic == SESSION_MAGIC)
+            .ok_or(SessionError::NotASession)?;
+
+        if header.version != SESSION_VERSION {
+            return Err(SessionError::UnsupportedVersion(header.version));
+        }
+
+        let mut records = Vec::new();
+
+        for frame in frames.filter(|frame| !frame.is_empty()) {
+            match postcard::from_bytes_cobs::<SessionRecord>(frame) {
+                Ok(record) => records.push(record),
+                Err(error) => {
+                    warn!(%error, records = records.len(), "Session file is truncated or corrupt, ignoring the rest");
+                    break;
+                }
+            }
+        }
+
+        Ok(Self { header, records })
+    }
+
+    pub const fn start(&self) -> LocalTime {
+        self.header.started
+    }
+
+    pub fn end(&self) -> LocalTime {
+        self.records
+            .last()
+            .map(|record| record.received)
+            .unwrap_or(self.header.started)
+    }
+}
+
+#[derive(Debug)]
+pub enum SessionError {
+    Io(io::Error),
+    NotASession,
+    UnsupportedVersion(u16),
+}
+
+impl Display for SessionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            SessionError::Io(error) => write!(f, "unable to read session file: {}", error),
+            SessionError::NotASession => write!(f, "file is not a session log"),
+            SessionError::UnsupportedVersion(version) => write!(
+                f,
+                "session file version {} is not supported (expected {})",
+                version, SESSION_VERSION
+            ),
+        }
+    }
+}
+
+impl Error for SessionError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SessionError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for SessionError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
@@ -0,0 +1,108 @@
+use std::{
+    env,
+    fs::{self, File, OpenOptions},
+    io::{self, ErrorKind, Write},
+    path::{Path, PathBuf},
+    thread,
+};
+
+use flume::{Receiver, Sender};
+use serde::Serialize;
+use tracing::{debug, error, trace, warn};
+
+use crate::time_manager::unit::LocalTime;
+
+use super::{SessionEvent, SessionHeader, SessionRecord, SESSION_EXTENSION};
+
+/// Directory used for session logs when `SESSION_DIRECTORY` is not set.
+const DEFAULT_SESSION_DIRECTORY: &str = "sessions";
+
+#[derive(Debug)]
+pub struct SessionRecorder {
+    sender: Sender<SessionRecord>,
+    path: PathBuf,
+}
+
+impl SessionRecorder {
+    /// Create a new session file in `directory` and spawn the thread writing to it
+    pub fn start(directory: impl AsRef<Path>) -> io::Result<Self> {
+        let started = LocalTime::now();
+
+        fs::create_dir_all(&directory)?;
+
+        let path = directory.as_ref().join(format!(
+            "{}.{}",
+            started.format_file_name(),
+            SESSION_EXTENSION
+        ));
+
+        let mut file = OpenOptions::new()
+            .create_new(true)
+            .append(true)
+            .open(&path)?;
+
+        write_frame(&mut file, &SessionHeader::new(started))?;
+        file.sync_data()?;
+
+        let (sender, receiver) = flume::unbounded();
+
+        thread::spawn(move || session_writer(file, receiver));
+
+        Ok(Self { sender, path })
+    }
+
+    pub fn record(&self, received: LocalTime, event: SessionEvent) {
+        if self.sender.send(SessionRecord { received, event }).is_err() {
+            warn!("Session writer has shut down, dropping record");
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+/// Start recording to the directory named by `SESSION_DIRECTORY`, logging any failure
+pub fn start_recording() -> Option<SessionRecorder> {
+    let directory = env::var_os("SESSION_DIRECTORY")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_SESSION_DIRECTORY));
+
+    match SessionRecorder::start(&directory) {
+        Ok(recorder) => {
+            debug!(path = %recorder.path().display(), "Recording session");
+            Some(recorder)
+        }
+        Err(error) => {
+            warn!(%error, directory = %directory.display(), "Failed to start session recording");
+            None
+        }
+    }
+}
+
+fn session_writer(mut file: File, receiver: Receiver<SessionRecord>) {
+    trace!("Session writer spawned");
+
+    while let Ok(record) = receiver.recv() {
+        if let Err(error) = write_frame(&mut file, &record) {
+            error!(%error, "Failed to write to session file");
+            return;
+        }
+
+        // Only sync once caught up so bursts of packets do not stall the writer
+        if receiver.is_empty() {
+            if let Err(error) = file.sync_data() {
+                warn!(%error, "Failed to sync session file");
+            }
+        }
+    }
+
+    trace!("SessionRecord channel closed, shutting down writer");
+}
+
+fn write_frame(file: &mut File, value: &impl Serialize) -> io::Result<()> {
+    let frame = postcard::to_allocvec_cobs(value)
+        .map_err(|error| io::Error::new(ErrorKind::InvalidData, error))?;
+
+    file.write_all(&frame)
+}
@@ -0,0 +1,256 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    hash::Hash,
+    thread,
+    time::Duration,
+};
+
+use flume::{Receiver, Sender};
+use iced::{futures::stream::BoxStream, Subscription};
+use iced_native::subscription::Recipe;
+use interlink::proto::{BarometerReading, FlightPhase, PacketDownData};
+use tracing::trace;
+
+use crate::{
+    comm::SerialEvent, element::instrument::altimeter::REFERENCE_SAMPLES,
+    time_manager::clock::ReplayClock,
+};
+
+use super::{Session, SessionEvent, SessionRecord};
+
+/// Longest the replay thread sleeps before re-checking the clock
+const PLAYER_TICK: Duration = Duration::from_millis(10);
+
+#[derive(Debug, Clone)]
+pub struct ReplaySubscription {
+    receiver: Receiver<SerialEvent>,
+    clock: ReplayClock,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum ReplayMessage {
+    TogglePause,
+    /// Seek to the given number of seconds after the start of the session
+    Seek(f64),
+    ChangeSpeed(ReplaySpeed),
+}
+
+impl ReplaySubscription {
+    pub fn start(session: Session) -> Self {
+        let (sender, receiver) = flume::unbounded();
+        let clock = ReplayClock::new(session.start(), session.end());
+
+        thread::spawn({
+            let clock = clock.clone();
+
+            move || replay_player(session, clock, sender)
+        });
+
+        Self { receiver, clock }
+    }
+
+    pub fn subscription(&self) -> Subscription<SerialEvent> {
+        Subscription::from_recipe(self.clone())
+    }
+
+    pub const fn clock(&self) -> &ReplayClock {
+        &self.clock
+    }
+
+    pub fn update(&self, message: ReplayMessage) {
+        match message {
+            ReplayMessage::TogglePause => self.clock.set_paused(!self.clock.is_paused()),
+            ReplayMessage::Seek(seconds) => self
+                .clock
+                .seek(self.clock.start() + time::Duration::seconds_f64(seconds)),
+            ReplayMessage::ChangeSpeed(speed) => self.clock.set_speed(speed.multiplier()),
+        }
+    }
+}
+
+impl<Hasher, Event> Recipe<Hasher, Event> for ReplaySubscription
+where
+    Hasher: std::hash::Hasher,
+{
+    type Output = SerialEvent;
+
+    fn hash(&self, state: &mut Hasher) {
+        // Only one session is ever replayed at a time
+        self.clock.start().hash(state);
+    }
+
+    fn stream(self: Box<Self>, _input: BoxStream<Event>) -> BoxStream<Self::Output> {
+        Box::pin(self.receiver.into_stream())
+    }
+}
+
+fn replay_player(session: Session, clock: ReplayClock, sender: Sender<SerialEvent>) {
+    trace!("Replay thread spawned");
+
+    let mut generation = clock.generation();
+    let mut next = 0;
+
+    loop {
+        if sender.is_disconnected() {
+            trace!("SerialEvent channel closed, shutting down replay thread");
+
+            return;
+        }
+
+        if clock.generation() != generation {
+            generation = clock.generation();
+
+            let now = clock.now();
+            next = session
+                .records
+                .partition_point(|record| record.received < now);
+
+            for event in restore_state(&session.records[..next]) {
+                sender.send(event).ok();
+            }
+        }
+
+        let record = match session.records.get(next) {
+            Some(record) => record,
+            None => {
+                // Keep the thread around so the session can be seeked back into
+                thread::sleep(PLAYER_TICK);
+                continue;
+            }
+        };
+
+        let remaining = record.received.duration_since(&clock.now());
+
+        if remaining.is_positive() {
+            let wall_remaining =
+                Duration::from_secs_f64(remaining.as_seconds_f64() / clock.speed());
+
+            thread::sleep(wall_remaining.min(PLAYER_TICK));
+            continue;
+        }
+
+        if sender.send(record.event.clone().into()).is_err() {
+            trace!("SerialEvent channel closed, shutting down replay thread");
+
+            return;
+        }
+
+        next += 1;
+    }
+}
+
+/// Events that bring the station into the state it was in after `history`
+fn restore_state(history: &[SessionRecord]) -> Vec<SerialEvent> {
+    let mut events = vec![
+        SerialEvent::Seeked {
+            pad_readings: pad_readings(history),
+        },
+        SerialEvent::Disconnected,
+    ];
+
+    let connected = history.iter().rev().find_map(|record| match record.event {
+        SessionEvent::Connected(method) => Some(Some(method)),
+        SessionEvent::Disconnected => Some(None),
+        SessionEvent::PacketReceived(_) => None,
+    });
+
+    if let Some(Some(method)) = connected {
+        events.push(SerialEvent::Connected { method, peer: None });
+
+        let hello = history.iter().rev().find(|record| {
+            matches!(
+                &record.event,
+                SessionEvent::PacketReceived(packet) if matches!(packet.data, PacketDownData::Hello(_))
+            )
+        });
+
+        if let Some(hello) = hello {
+            events.push(hello.event.clone().into());
+        }
+    }
+
+    events
+}
+
+/// The barometer readings the altimeter would have taken as the pressure on the
+/// pad, had the session been played from the start, so a seek into the flight
+/// does not measure from wherever the vehicle was at the time
+fn pad_readings(history: &[SessionRecord]) -> Vec<BarometerReading> {
+    history
+        .iter()
+        .filter_map(|record| match &record.event {
+            SessionEvent::PacketReceived(packet) => Some(&packet.data),
+            _ => None,
+        })
+        .take_while(|data| {
+            !matches!(
+                data,
+                PacketDownData::FlightPhase(phase)
+                    if !matches!(phase, FlightPhase::Idle | FlightPhase::Armed)
+            )
+        })
+        .filter_map(|data| match data {
+            PacketDownData::Barometer(reading) => Some(*reading),
+            _ => None,
+        })
+        .take(REFERENCE_SAMPLES as usize)
+        .collect()
+}
+
+impl From<SessionEvent> for SerialEvent {
+    fn from(event: SessionEvent) -> Self {
+        match event {
+            SessionEvent::PacketReceived(packet) => SerialEvent::PacketReceived(packet),
+            SessionEvent::Connected(method) => SerialEvent::Connected { method, peer: None },
+            SessionEvent::Disconnected => SerialEvent::Disconnected,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplaySpeed {
+    Quarter,
+    Half,
+    Normal,
+    Double,
+    Quadruple,
+    Octuple,
+    Hexadecuple,
+}
+
+impl ReplaySpeed {
+    pub const ALL: &'static [ReplaySpeed] = &[
+        ReplaySpeed::Quarter,
+        ReplaySpeed::Half,
+        ReplaySpeed::Normal,
+        ReplaySpeed::Double,
+        ReplaySpeed::Quadruple,
+        ReplaySpeed::Octuple,
+        ReplaySpeed::Hexadecuple,
+    ];
+
+    pub const fn multiplier(self) -> f64 {
+        match self {
+            ReplaySpeed::Quarter => 0.25,
+            ReplaySpeed::Half => 0.5,
+            ReplaySpeed::Normal => 1.0,
+            ReplaySpeed::Double => 2.0,
+            ReplaySpeed::Quadruple => 4.0,
+            ReplaySpeed::Octuple => 8.0,
+            ReplaySpeed::Hexadecuple => 16.0,
+        }
+    }
+
+    pub fn from_multiplier(multiplier: f64) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|speed| (speed.multiplier() - multiplier).abs() < f64::EPSILON)
+    }
+}
+
+impl Display for ReplaySpeed {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}x", self.multiplier())
+    }
+}
@@ -118,6 +118,21 @@ impl container::StyleSheet for Tooltip {
     }
 }
 
+/// Full width notice drawn in the given color
+pub struct Banner(pub colors::Color);
+
+impl container::StyleSheet for Banner {
+    fn style(&self) -> container::Style {
+        container::Style {
+            border_color: self.0.into(),
+            border_width: 2.0,
+            border_radius: 10.0,
+            background: colors::SURFACE.into(),
+            text_color: self.0.into(),
+        }
+    }
+}
+
 pub mod fonts {
     pub use roboto_mono::REGULAR as MONOSPACE;
     // pub use _ as INTERFACE;
@@ -161,6 +176,8 @@ pub mod fonts {
 }
 
 pub mod colors {
+    use interlink::proto::{FlightPhase, PyroChannel};
+
     pub const TEXT: Color = Color::from_rgb(0xEE, 0xEE, 0xEE);
     pub const SECONDARY_TEXT: Color = Color::from_rgb(0x88, 0x88, 0x88);
     pub const GOOD: Color = Color::from_rgb(0x00, 0xEE, 0x00);
@@ -178,6 +195,39 @@ pub mod colors {
     pub const GRID_LINES: Color = Color::from_rgb(0x45, 0x45, 0x45);
     pub const AXIS: Color = Color::from_rgb(0xEE, 0xEE, 0xEE);
 
+    pub const SKY: Color = Color::from_rgb(0x1F, 0x4E, 0x79);
+    pub const GROUND: Color = Color::from_rgb(0x6B, 0x47, 0x26);
+
+    /// Color each phase of flight is shown in
+    pub const fn flight_phase(phase: FlightPhase) -> Color {
+        match phase {
+            FlightPhase::Idle => SECONDARY_TEXT,
+            FlightPhase::Armed => WARNING,
+            FlightPhase::Boost => Color::from_rgb(0xEE, 0x77, 0x00),
+            FlightPhase::Coast => Color::from_rgb(0x33, 0x99, 0xFF),
+            FlightPhase::Apogee => Color::from_rgb(0xAA, 0x66, 0xFF),
+            FlightPhase::Descent => ACCENT,
+            FlightPhase::Landed => GOOD,
+            FlightPhase::Fault => ERROR,
+        }
+    }
+
+    pub const PYRO_SAFE: Color = GOOD;
+    pub const PYRO_ARMED: Color = ERROR;
+    pub const PYRO_FIRED: Color = Color::from_rgb(0xEE, 0x77, 0x00);
+
+    /// Color a pyro channel is shown in, by whether it is safe, armed or fired
+    pub const fn pyro_channel(channel: PyroChannel) -> Color {
+        if channel.fired {
+            PYRO_FIRED
+        } else if channel.armed {
+            PYRO_ARMED
+        } else {
+            PYRO_SAFE
+        }
+    }
+
+    #[derive(Debug, Clone, Copy)]
     pub struct Color {
         pub r: u8,
         pub g: u8,
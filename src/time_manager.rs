@@ -2,33 +2,46 @@ use time::Duration;
 
 use self::{
     base::TimeBase,
+    clock::Clock,
+    clock_model::{ClockModel, SyncSample},
+    mission::MissionClock,
     unit::{LocalTime, VehicleTime},
 };
 
 #[derive(Debug, Clone)]
 pub struct TimeManager {
+    clock: Clock,
+
     now: LocalTime,
     ground_control_on: LocalTime,
 
     last_packet: Option<LocalTime>,
     vehicle_time: Option<VehicleTime>,
+    clock_model: ClockModel,
+    last_sync: Option<SyncSample>,
 
-    mission_start: Option<LocalTime>,
+    mission: MissionClock,
 }
 
 pub mod base;
+pub mod clock;
+pub mod clock_model;
+pub mod mission;
 pub mod unit;
 
 impl TimeManager {
-    pub fn setup() -> Self {
-        let now = LocalTime::now();
+    pub fn setup(clock: Clock) -> Self {
+        let now = clock.now();
 
         Self {
+            clock,
             now,
             // Artificially sync the local time with the ground control time
             ground_control_on: now.quantize(),
             vehicle_time: None,
-            mission_start: None,
+            clock_model: ClockModel::default(),
+            last_sync: None,
+            mission: MissionClock::default(),
             last_packet: None,
         }
     }
@@ -41,39 +54,88 @@ impl TimeManager {
                 .as_ref()
                 .map(VehicleTime::as_duration)
                 .unwrap_or(Duration::ZERO),
-            TimeBase::Mission => self
-                .mission_start
-                .as_ref()
-                .map(|mission_start| self.now.duration_since(mission_start))
-                .unwrap_or(Duration::ZERO),
+            TimeBase::Mission => self.mission.at(self.now).unwrap_or(Duration::ZERO),
         }
     }
 
     pub fn rebase_vehicle_time(&self, vehicle_time: VehicleTime, time_base: TimeBase) -> Duration {
         match time_base {
-            TimeBase::GroundControl => vehicle_time
-                .received()
+            TimeBase::GroundControl => self
+                .vehicle_to_local(vehicle_time)
                 .duration_since(&self.ground_control_on),
             TimeBase::VehicleTime => vehicle_time.as_duration(),
             TimeBase::Mission => self
-                .mission_start
-                .as_ref()
-                .map(|mission_start| vehicle_time.received().duration_since(mission_start))
+                .mission
+                .at(self.vehicle_to_local(vehicle_time))
                 .unwrap_or(Duration::ZERO),
         }
     }
 
     pub fn update_now(&mut self) {
-        self.now = LocalTime::now();
+        self.now = self.clock.now();
     }
 
     pub const fn now(&self) -> LocalTime {
         self.now
     }
 
+    /// Station time at which the vehicle's clock read `vehicle_time`, according
+    /// to the clock model rather than when `vehicle_time` arrived
+    pub fn vehicle_to_local(&self, vehicle_time: VehicleTime) -> LocalTime {
+        self.clock_model.to_local(vehicle_time)
+    }
+
+    pub const fn clock_model(&self) -> &ClockModel {
+        &self.clock_model
+    }
+
+    /// The most recent time sync exchange with the vehicle
+    pub const fn last_sync(&self) -> Option<SyncSample> {
+        self.last_sync
+    }
+
+    /// Forget what is known about the vehicle's clock, such as when it may have rebooted
+    pub fn reset_clock_model(&mut self) {
+        self.clock_model.reset();
+        self.last_sync = None;
+    }
+
+    pub fn time_synced(&mut self, sync: SyncSample) {
+        self.last_sync = Some(sync);
+        self.clock_model.add_sync(sync);
+    }
+
+    pub const fn mission(&self) -> &MissionClock {
+        &self.mission
+    }
+
+    /// Start mission time now, counting down from `countdown` to liftoff
+    pub fn start_mission(&mut self, countdown: Duration) {
+        self.mission.start(self.now, countdown);
+    }
+
+    /// Start mission time from the moment the vehicle lifted off, by its own clock
+    pub fn liftoff(&mut self, vehicle_time: VehicleTime) {
+        self.mission
+            .start(self.vehicle_to_local(vehicle_time), Duration::ZERO);
+    }
+
+    pub fn hold_mission(&mut self) {
+        self.mission.hold(self.now);
+    }
+
+    pub fn resume_mission(&mut self) {
+        self.mission.resume(self.now);
+    }
+
+    pub fn reset_mission(&mut self) {
+        self.mission.reset();
+    }
+
     pub fn packet_received(&mut self, vehicle_time: VehicleTime) {
-        self.last_packet = Some(LocalTime::now());
+        self.last_packet = Some(vehicle_time.received());
         self.vehicle_time = Some(vehicle_time);
+        self.clock_model.add_sample(vehicle_time);
     }
 
     pub fn duration_since_last_packet(&self) -> Option<Duration> {
@@ -84,8 +146,15 @@ impl TimeManager {
 }
 
 pub fn format_duration(duration: time::Duration) -> String {
+    let (sign, duration) = if duration.is_negative() {
+        ("-", duration.abs())
+    } else {
+        ("", duration)
+    };
+
     format!(
-        "{:02}:{:02}:{:02}.{:01}",
+        "{}{:02}:{:02}:{:02}.{:01}",
+        sign,
         duration.whole_hours(),
         duration.whole_minutes() % 60,
         duration.whole_seconds() % 60,
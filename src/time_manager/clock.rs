@@ -0,0 +1,145 @@
+use std::{
+    sync::{Arc, Mutex, MutexGuard},
+    time::Instant,
+};
+
+use time::Duration;
+
+use super::unit::LocalTime;
+
+/// Source of the current time for a [`TimeManager`](super::TimeManager)
+#[derive(Debug, Clone)]
+pub enum Clock {
+    /// Follow the station's wall clock
+    Local,
+    /// Follow the playback position of a recorded session
+    Replay(ReplayClock),
+}
+
+impl Clock {
+    pub fn now(&self) -> LocalTime {
+        match self {
+            Clock::Local => LocalTime::now(),
+            Clock::Replay(replay) => replay.now(),
+        }
+    }
+}
+
+/// Shared playback position of a recorded session
+///
+/// Cloning a `ReplayClock` yields a handle to the same clock, so the replay
+/// thread and the UI always agree on the current replay time.
+#[derive(Debug, Clone)]
+pub struct ReplayClock {
+    state: Arc<Mutex<ReplayClockState>>,
+}
+
+#[derive(Debug)]
+struct ReplayClockState {
+    start: LocalTime,
+    end: LocalTime,
+
+    /// Replay time at the moment `anchor` was taken
+    position: LocalTime,
+    anchor: Instant,
+
+    speed: f64,
+    paused: bool,
+    /// Incremented on every seek so observers can detect jumps in time
+    generation: u64,
+}
+
+impl ReplayClockState {
+    fn now(&self) -> LocalTime {
+        if self.paused {
+            return self.position;
+        }
+
+        let elapsed = Duration::seconds_f64(self.anchor.elapsed().as_secs_f64() * self.speed);
+
+        (self.position + elapsed).min(self.end)
+    }
+
+    fn rebase(&mut self) {
+        self.position = self.now();
+        self.anchor = Instant::now();
+    }
+}
+
+impl ReplayClock {
+    /// Create a paused clock positioned at `start`
+    pub fn new(start: LocalTime, end: LocalTime) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(ReplayClockState {
+                start,
+                end,
+                position: start,
+                anchor: Instant::now(),
+                speed: 1.0,
+                paused: true,
+                generation: 0,
+            })),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, ReplayClockState> {
+        self.state.lock().expect("replay clock mutex poisoned")
+    }
+
+    pub fn now(&self) -> LocalTime {
+        self.lock().now()
+    }
+
+    pub fn start(&self) -> LocalTime {
+        self.lock().start
+    }
+
+    /// How far into the session playback is
+    pub fn elapsed(&self) -> Duration {
+        let state = self.lock();
+
+        state.now().duration_since(&state.start)
+    }
+
+    /// Length of the session being replayed
+    pub fn duration(&self) -> Duration {
+        let state = self.lock();
+
+        state.end.duration_since(&state.start)
+    }
+
+    pub fn speed(&self) -> f64 {
+        self.lock().speed
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.lock().paused
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.lock().generation
+    }
+
+    pub fn set_speed(&self, speed: f64) {
+        let mut state = self.lock();
+
+        state.rebase();
+        state.speed = speed;
+    }
+
+    pub fn set_paused(&self, paused: bool) {
+        let mut state = self.lock();
+
+        state.rebase();
+        state.paused = paused;
+    }
+
+    /// Jump to `position`, clamped to the bounds of the session
+    pub fn seek(&self, position: LocalTime) {
+        let mut state = self.lock();
+
+        state.position = position.clamp(state.start, state.end);
+        state.anchor = Instant::now();
+        state.generation += 1;
+    }
+}
@@ -0,0 +1,242 @@
+use std::collections::VecDeque;
+
+use time::Duration;
+
+use super::unit::{LocalTime, VehicleTime};
+
+/// Seconds of vehicle time covered by the samples the model is fitted to
+const WINDOW: f64 = 30.0;
+/// Seconds of vehicle time between samples, of the packets sent in between
+/// only the one that arrived quickest is kept
+const SAMPLE_INTERVAL: f64 = 0.1;
+/// Seconds of vehicle time the samples must span before any drift is fitted
+const MIN_DRIFT_SPAN: f64 = 5.0;
+/// Samples further than this many standard deviations from the first fit are
+/// left out of the second
+const OUTLIER_DEVIATIONS: f64 = 3.0;
+/// Smallest standard deviation assumed in seconds, so that a very steady link
+/// does not have most of its samples rejected
+const MIN_DEVIATION: f64 = 0.001;
+/// Number of time sync exchanges kept, of which the quickest sets the offset
+const SYNC_HISTORY: usize = 16;
+
+/// Midpoint of a time sync exchange with the vehicle, when its clock is known
+/// to have read `vehicle` give or take half the round trip
+#[derive(Debug, Clone, Copy)]
+pub struct SyncSample {
+    pub vehicle: Duration,
+    pub station: LocalTime,
+    /// Time the request and response spent on the link, leaving out how long
+    /// the vehicle took to answer
+    pub round_trip: Duration,
+}
+
+/// Linear relationship between the vehicle's clock and the station's
+#[derive(Debug, Clone, Copy)]
+pub struct ClockFit {
+    /// Station seconds after the model's reference at vehicle time zero
+    offset: f64,
+    /// Station seconds elapsed per vehicle second
+    rate: f64,
+    /// Standard deviation of the samples kept around the fit, in seconds
+    pub jitter: f64,
+    /// Number of samples that were kept
+    pub samples: usize,
+    /// Round trip of the time sync exchange the offset was taken from, if any
+    pub round_trip: Option<Duration>,
+}
+
+impl ClockFit {
+    /// How much faster the station's clock runs than the vehicle's, in parts per million
+    pub fn drift_ppm(&self) -> f64 {
+        (self.rate - 1.0) * 1e6
+    }
+}
+
+/// Fits the offset and drift between the vehicle's clock and the station's
+/// from when packets arrive, so vehicle timestamps can be placed in station
+/// time without the jitter of the link and user interface
+///
+/// Arrivals include however long packets took to arrive, so once the vehicle
+/// answers time sync requests the offset is instead taken from the quickest
+/// recent exchange, where the time spent on the link cancels out.
+#[derive(Debug, Clone, Default)]
+pub struct ClockModel {
+    /// Station time the samples are measured from
+    reference: Option<LocalTime>,
+    /// Vehicle and station seconds of each sample, oldest first
+    samples: VecDeque<(f64, f64)>,
+    /// Vehicle and station seconds at the midpoint of each time sync
+    /// exchange with its round trip, oldest first
+    syncs: VecDeque<(f64, f64, Duration)>,
+    fit: Option<ClockFit>,
+}
+
+impl ClockModel {
+    pub fn add_sample(&mut self, vehicle_time: VehicleTime) {
+        let reference = *self.reference.get_or_insert(vehicle_time.received());
+
+        let vehicle = vehicle_time.as_duration().as_seconds_f64();
+        let station = vehicle_time
+            .received()
+            .duration_since(&reference)
+            .as_seconds_f64();
+
+        match self.samples.back_mut() {
+            // Too far back to be a late packet, so the vehicle must have rebooted
+            Some(&mut (last_vehicle, _)) if vehicle < last_vehicle - WINDOW => {
+                self.reset();
+                self.add_sample(vehicle_time);
+
+                return;
+            }
+            // A late packet, which arrived no sooner than those already sampled
+            Some(&mut (last_vehicle, _)) if vehicle < last_vehicle => return,
+            Some(last) if vehicle - last.0 < SAMPLE_INTERVAL => {
+                // Packets can only be delayed, so the one that arrived soonest
+                // after it was sent is closest to the true offset
+                if station - vehicle < last.1 - last.0 {
+                    *last = (vehicle, station);
+                } else {
+                    return;
+                }
+            }
+            _ => self.samples.push_back((vehicle, station)),
+        }
+
+        while let Some(&(oldest, _)) = self.samples.front() {
+            if vehicle - oldest <= WINDOW {
+                break;
+            }
+
+            self.samples.pop_front();
+        }
+
+        self.fit = self.refit();
+    }
+
+    pub fn add_sync(&mut self, sync: SyncSample) {
+        let reference = *self.reference.get_or_insert(sync.station);
+
+        let vehicle = sync.vehicle.as_seconds_f64();
+        let station = sync.station.duration_since(&reference).as_seconds_f64();
+
+        if self.syncs.len() == SYNC_HISTORY {
+            self.syncs.pop_front();
+        }
+
+        self.syncs.push_back((vehicle, station, sync.round_trip));
+        self.syncs
+            .retain(|&(sync_vehicle, _, _)| vehicle - sync_vehicle <= WINDOW);
+
+        self.fit = self.refit();
+    }
+
+    /// Station time at which the vehicle's clock read `vehicle_time`, or when
+    /// it arrived if there is no fit yet
+    pub fn to_local(&self, vehicle_time: VehicleTime) -> LocalTime {
+        match (self.reference, self.fit) {
+            (Some(reference), Some(fit)) => {
+                let vehicle = vehicle_time.as_duration().as_seconds_f64();
+
+                reference + Duration::seconds_f64(fit.offset + fit.rate * vehicle)
+            }
+            _ => vehicle_time.received(),
+        }
+    }
+
+    pub const fn fit(&self) -> Option<ClockFit> {
+        self.fit
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Fit a line to the samples, then again to those that were not outliers
+    fn refit(&self) -> Option<ClockFit> {
+        let samples = self.samples.iter().copied().collect::<Vec<_>>();
+
+        let (offset, rate) = least_squares(&samples)?;
+        let spread = deviation(&samples, offset, rate).max(MIN_DEVIATION);
+
+        let kept = samples
+            .into_iter()
+            .filter(|&(vehicle, station)| {
+                (station - (offset + rate * vehicle)).abs() <= OUTLIER_DEVIATIONS * spread
+            })
+            .collect::<Vec<_>>();
+
+        let (offset, rate) = least_squares(&kept).unwrap_or((offset, rate));
+        let jitter = deviation(&kept, offset, rate);
+
+        // The quickest exchange is the one least thrown off by an uneven link
+        let quickest = self
+            .syncs
+            .iter()
+            .copied()
+            .min_by_key(|&(_, _, round_trip)| round_trip);
+
+        let (offset, round_trip) = match quickest {
+            Some((vehicle, station, round_trip)) => (station - rate * vehicle, Some(round_trip)),
+            None => (offset, None),
+        };
+
+        Some(ClockFit {
+            offset,
+            rate,
+            jitter,
+            samples: kept.len(),
+            round_trip,
+        })
+    }
+}
+
+/// Offset and rate of the line through `samples` with the least squared error,
+/// taking the rate to be one if they span too little time to tell
+fn least_squares(samples: &[(f64, f64)]) -> Option<(f64, f64)> {
+    let &(first, _) = samples.first()?;
+    let &(last, _) = samples.last()?;
+
+    let count = samples.len() as f64;
+    let (sum_vehicle, sum_station) = samples.iter().fold(
+        (0.0, 0.0),
+        |(sum_vehicle, sum_station), &(vehicle, station)| {
+            (sum_vehicle + vehicle, sum_station + station)
+        },
+    );
+    let (mean_vehicle, mean_station) = (sum_vehicle / count, sum_station / count);
+
+    let (covariance, variance) =
+        samples
+            .iter()
+            .fold((0.0, 0.0), |(covariance, variance), &(vehicle, station)| {
+                (
+                    covariance + (vehicle - mean_vehicle) * (station - mean_station),
+                    variance + (vehicle - mean_vehicle) * (vehicle - mean_vehicle),
+                )
+            });
+
+    let rate = if last - first >= MIN_DRIFT_SPAN && variance > 0.0 {
+        covariance / variance
+    } else {
+        1.0
+    };
+
+    Some((mean_station - rate * mean_vehicle, rate))
+}
+
+/// Root mean square distance of `samples` from a line, in seconds
+fn deviation(samples: &[(f64, f64)], offset: f64, rate: f64) -> f64 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+
+    let sum_squares = samples
+        .iter()
+        .map(|&(vehicle, station)| station - (offset + rate * vehicle))
+        .map(|residual| residual * residual)
+        .sum::<f64>();
+
+    (sum_squares / samples.len() as f64).sqrt()
+}
@@ -0,0 +1,88 @@
+use time::Duration;
+
+use super::unit::LocalTime;
+
+/// Mission time, counting up from liftoff and negative during the countdown
+///
+/// The clock can be held, freezing mission time until it is resumed. Times
+/// from before a hold keep the mission time they had when it started.
+#[derive(Debug, Clone, Default)]
+pub struct MissionClock {
+    /// When mission time would read zero had it never been held
+    zero: Option<LocalTime>,
+    /// Holds that have been resumed, as when they started and how long they lasted
+    holds: Vec<(LocalTime, Duration)>,
+    /// When the current hold started
+    holding_since: Option<LocalTime>,
+}
+
+impl MissionClock {
+    /// Start the clock at `now`, reading minus `countdown`
+    pub fn start(&mut self, now: LocalTime, countdown: Duration) {
+        *self = Self {
+            zero: Some(now + countdown),
+            ..Self::default()
+        };
+    }
+
+    /// Freeze mission time at its value at `now`, if still counting down
+    pub fn hold(&mut self, now: LocalTime) {
+        if self.can_hold(now) {
+            self.holding_since = Some(now);
+        }
+    }
+
+    /// Let mission time carry on from where it was held
+    pub fn resume(&mut self, now: LocalTime) {
+        if let Some(since) = self.holding_since.take() {
+            self.holds.push((since, now.duration_since(&since)));
+        }
+    }
+
+    /// Stop the clock and forget when it was started
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    pub const fn is_started(&self) -> bool {
+        self.zero.is_some()
+    }
+
+    pub const fn is_holding(&self) -> bool {
+        self.holding_since.is_some()
+    }
+
+    pub const fn is_running(&self) -> bool {
+        self.is_started() && !self.is_holding()
+    }
+
+    /// Whether the clock can be held at `now`, as once the vehicle has lifted
+    /// off there is nothing left to hold for
+    pub fn can_hold(&self, now: LocalTime) -> bool {
+        matches!(self.at(now), Some(time) if self.is_running() && time.is_negative())
+    }
+
+    /// Mission time at the local `time`, if the clock has been started
+    pub fn at(&self, time: LocalTime) -> Option<Duration> {
+        let zero = self.zero?;
+
+        let current_hold = self
+            .holding_since
+            .map(|since| (since, time.duration_since(&since).max(Duration::ZERO)));
+
+        // Only the part of each hold that had happened by `time` is taken off
+        let held = self
+            .holds
+            .iter()
+            .copied()
+            .chain(current_hold)
+            .map(|(since, duration)| {
+                time.duration_since(&since)
+                    .max(Duration::ZERO)
+                    .min(duration)
+            })
+            .fold(Duration::ZERO, |total, held| total + held);
+
+        Some(time.duration_since(&zero) - held)
+    }
+}
@@ -1,9 +1,10 @@
-use std::sync::Once;
+use std::{ops::Add, sync::Once};
 
+use serde::{Deserialize, Serialize};
 use time::{macros::format_description, Duration, OffsetDateTime, Time};
 use tracing::error;
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct LocalTime {
     date_time: OffsetDateTime,
 }
@@ -45,6 +46,11 @@ impl LocalTime {
         self.date_time - local_time.date_time
     }
 
+    /// Microseconds since the Unix epoch, as sent to the vehicle
+    pub const fn unix_micros(&self) -> i64 {
+        (self.date_time.unix_timestamp_nanos() / 1_000) as i64
+    }
+
     pub const fn is_utc(&self) -> bool {
         self.date_time.offset().is_utc()
     }
@@ -56,6 +62,25 @@ impl LocalTime {
             ))
             .expect("unable to format date time")
     }
+
+    /// Format an `LocalTime` in a form that is safe to use in a file name
+    pub fn format_file_name(&self) -> String {
+        self.date_time
+            .format(format_description!(
+                "[year]-[month]-[day]_[hour repr:24]-[minute]-[second]"
+            ))
+            .expect("unable to format date time")
+    }
+}
+
+impl Add<Duration> for LocalTime {
+    type Output = Self;
+
+    fn add(self, duration: Duration) -> Self {
+        Self {
+            date_time: self.date_time + duration,
+        }
+    }
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -65,9 +90,12 @@ pub struct VehicleTime {
 }
 
 impl VehicleTime {
-    pub fn from_packet(vehicle_time: interlink::vehicle_time::VehicleTime) -> Self {
+    pub const fn from_packet(
+        vehicle_time: interlink::vehicle_time::VehicleTime,
+        received_time: LocalTime,
+    ) -> Self {
         Self {
-            received_time: LocalTime::now(),
+            received_time,
             vehicle_time,
         }
     }
@@ -1,5 +1,7 @@
+use std::{env, fmt::Display, str::FromStr};
+
 use insomnia::{InhibitionManager, Lock, LockType};
-use tracing::{trace, warn};
+use tracing::{error, trace, warn};
 
 pub fn inhibit_sleep() -> Option<Box<dyn Lock>> {
     match insomnia::manager() {
@@ -26,3 +28,18 @@ pub fn inhibit_sleep() -> Option<Box<dyn Lock>> {
         }
     }
 }
+
+/// Parse an environment variable, falling back to `default` if it is unset or invalid
+pub fn env_or<T: FromStr>(variable: &str, default: T) -> T
+where
+    T::Err: Display,
+{
+    match env::var(variable) {
+        Ok(value) => value.parse().unwrap_or_else(|error| {
+            error!(%error, %value, variable, "Invalid configuration, using the default");
+
+            default
+        }),
+        Err(_) => default,
+    }
+}
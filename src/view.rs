@@ -1,3 +1,8 @@
 pub mod acceleration;
+pub mod altitude;
+pub mod attitude;
 pub mod default;
 pub mod magnetic_field;
+pub mod position;
+pub mod power;
+pub mod temperature;
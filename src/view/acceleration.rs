@@ -16,8 +16,9 @@ pub fn view(app: &mut InstrumentCluster) -> Element<Message> {
                 .height(Length::Fill)
                 .push(telemetry_status(
                     &app.time,
-                    app.interlink,
+                    app.link.as_ref(),
                     app.vehicle.as_ref(),
+                    app.flight_phase,
                 ))
                 .push(
                     Container::new(
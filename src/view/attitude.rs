@@ -0,0 +1,32 @@
+use iced::{Column, Element, Length, Row};
+
+use crate::{
+    element::{ground_station_status::ground_station_status, telemetry_status::telemetry_status},
+    InstrumentCluster, Message,
+};
+
+pub fn view(app: &mut InstrumentCluster) -> Element<Message> {
+    Column::new()
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .spacing(10)
+        .push(
+            Row::new()
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .push(telemetry_status(
+                    &app.time,
+                    app.link.as_ref(),
+                    app.vehicle.as_ref(),
+                    app.flight_phase,
+                ))
+                .push(ground_station_status(&app.time)),
+        )
+        .push(
+            Row::new()
+                .width(Length::Fill)
+                .height(Length::FillPortion(3))
+                .push(app.instruments.attitude.view(true).map(Message::Instrument)),
+        )
+        .into()
+}
@@ -2,19 +2,25 @@ use std::borrow::Cow;
 
 use iced::{
     button, pick_list, window::Mode, Align, Button, Column, Container, Element,
-    HorizontalAlignment, Length, PickList, Row, Text,
+    HorizontalAlignment, Length, PickList, Row, Space, Text,
 };
-use interlink::{phy::InterlinkMethod, proto::VehicleIdentification};
+use interlink::proto::{FlightPhase, VehicleIdentification};
 
 use crate::{
+    comm::LinkStatus,
     element::{
+        command_history::command_history,
         ground_station_status::ground_station_status,
         instrument::{
-            data_view::{Accelerometer, Magnetometer, Temperature},
+            altimeter::AltimeterInstrument,
+            attitude::AttitudeInstrument,
+            data_view::{Accelerometer, Altitude, Magnetometer, Power, Temperature},
+            position::PositionInstrument,
+            power::PowerInstrument,
             time_series::TimeSeriesInstrument,
             vector::VectorInstrument,
-            PlaceholderInstrument,
         },
+        protocol_banner::protocol_banner,
         telemetry_status::telemetry_status,
     },
     style,
@@ -23,16 +29,42 @@ use crate::{
 };
 
 pub fn view(app: &mut InstrumentCluster) -> Element<Message> {
+    let can_command = app.link.is_some() && app.can_command();
+    // Only a live vehicle can be fired, but a replayed one's channels are still shown
+    let can_fire = can_command && app.transport.is_some() && app.can_fire();
+
+    let replay_controls = app.replay.as_ref().map(|replay| {
+        app.replay_controls
+            .view(replay.clock())
+            .map(Message::Replay)
+    });
+
+    // Replayed vehicles cannot be commanded
+    let command_controls = app
+        .transport
+        .as_ref()
+        .map(|_| app.command_controls.view(can_command).map(Message::Command));
+    let pyro_controls = app.pyro_controls.view(can_fire).map(Message::Pyro);
+    let mission_controls = app.mission_controls.view(&app.time).map(Message::Mission);
+    let command_history = app
+        .transport
+        .as_ref()
+        .map(|_| command_history(&app.commands));
+
     Column::new()
         .width(Length::Fill)
         .height(Length::Fill)
         .spacing(10)
+        .push(protocol_banner(app.compatibility))
         .push(top_row(
+            &mut app.instruments.altitude_time,
             &mut app.instruments.temperature,
+            &mut app.instruments.altimeter,
             &app.time,
             app.time_base,
-            app.interlink,
+            app.link.as_ref(),
             app.vehicle.as_ref(),
+            app.flight_phase,
         ))
         .push(
             Row::new()
@@ -42,19 +74,33 @@ pub fn view(app: &mut InstrumentCluster) -> Element<Message> {
                 .push(left_column(
                     &mut app.instruments.magnetic_field_time,
                     &mut app.instruments.magnetic_field_vector,
+                    &mut app.instruments.position,
+                    &mut app.instruments.attitude,
                     &app.time,
                     app.time_base,
                 ))
                 .push(
-                    Container::new(control_cluster(
-                        &mut app.time_base_picker,
-                        app.time_base,
-                        app.window_size,
-                        app.window_focused,
-                        &mut app.fullscreen_button,
-                        app.window_mode,
-                        &mut app.quit_button,
-                    ))
+                    Container::new(
+                        Column::new()
+                            .push(control_cluster(
+                                &mut app.time_base_picker,
+                                app.time_base,
+                                mission_controls,
+                                app.window_size,
+                                app.window_focused,
+                                &mut app.fullscreen_button,
+                                app.window_mode,
+                                &mut app.quit_button,
+                                replay_controls,
+                                command_controls,
+                                pyro_controls,
+                            ))
+                            .push(command_history.unwrap_or_else(|| {
+                                Space::new(Length::Shrink, Length::Shrink).into()
+                            }))
+                            .spacing(10)
+                            .align_items(Align::Center),
+                    )
                     .width(Length::FillPortion(3))
                     .height(Length::Fill)
                     .center_x()
@@ -63,6 +109,8 @@ pub fn view(app: &mut InstrumentCluster) -> Element<Message> {
                 .push(right_column(
                     &mut app.instruments.acceleration_time,
                     &mut app.instruments.acceleration_vector,
+                    &mut app.instruments.power,
+                    &mut app.instruments.power_time,
                     &app.time,
                     app.time_base,
                 )),
@@ -70,25 +118,33 @@ pub fn view(app: &mut InstrumentCluster) -> Element<Message> {
         .into()
 }
 
+#[allow(clippy::too_many_arguments)]
 fn top_row<'app>(
+    altitude_time: &'app mut TimeSeriesInstrument<Altitude>,
     temperature: &'app mut TimeSeriesInstrument<Temperature>,
+    altimeter: &'app mut AltimeterInstrument,
     time: &'app TimeManager,
     time_base: TimeBase,
-    interlink: Option<InterlinkMethod>,
+    link: Option<&LinkStatus>,
     vehicle: Option<&VehicleIdentification>,
+    flight_phase: Option<FlightPhase>,
 ) -> Element<'app, Message> {
     Row::new()
         .width(Length::Fill)
         .height(Length::Fill)
         .spacing(10)
-        .push(telemetry_status(time, interlink, vehicle))
-        .push(PlaceholderInstrument::view().map(Message::Instrument))
+        .push(telemetry_status(time, link, vehicle, flight_phase))
+        .push(
+            altitude_time
+                .view(time, time_base, false)
+                .map(Message::Instrument),
+        )
         .push(
             temperature
                 .view(time, time_base, false)
                 .map(Message::Instrument),
         )
-        .push(PlaceholderInstrument::view().map(Message::Instrument))
+        .push(altimeter.view(false).map(Message::Instrument))
         .push(ground_station_status(time))
         .into()
 }
@@ -96,6 +152,8 @@ fn top_row<'app>(
 fn left_column<'app>(
     magnetic_field_time: &'app mut TimeSeriesInstrument<Magnetometer>,
     magnetic_field_vector: &'app mut VectorInstrument<Magnetometer>,
+    position: &'app mut PositionInstrument,
+    attitude: &'app mut AttitudeInstrument,
     time: &'app TimeManager,
     time_base: TimeBase,
 ) -> Element<'app, Message> {
@@ -109,14 +167,16 @@ fn left_column<'app>(
                 .map(Message::Instrument),
         )
         .push(magnetic_field_vector.view(false).map(Message::Instrument))
-        .push(PlaceholderInstrument::view().map(Message::Instrument))
-        .push(PlaceholderInstrument::view().map(Message::Instrument))
+        .push(position.view(false).map(Message::Instrument))
+        .push(attitude.view(false).map(Message::Instrument))
         .into()
 }
 
 fn right_column<'app>(
     acceleration_time: &'app mut TimeSeriesInstrument<Accelerometer>,
     acceleration_vector: &'app mut VectorInstrument<Accelerometer>,
+    power: &'app mut PowerInstrument,
+    power_time: &'app mut TimeSeriesInstrument<Power>,
     time: &'app TimeManager,
     time_base: TimeBase,
 ) -> Element<'app, Message> {
@@ -130,19 +190,28 @@ fn right_column<'app>(
                 .map(Message::Instrument),
         )
         .push(acceleration_vector.view(false).map(Message::Instrument))
-        .push(PlaceholderInstrument::view().map(Message::Instrument))
-        .push(PlaceholderInstrument::view().map(Message::Instrument))
+        .push(power.view(false).map(Message::Instrument))
+        .push(
+            power_time
+                .view(time, time_base, false)
+                .map(Message::Instrument),
+        )
         .into()
 }
 
+#[allow(clippy::too_many_arguments)]
 fn control_cluster<'app>(
     time_base_picker: &'app mut pick_list::State<TimeBase>,
     time_base: TimeBase,
+    mission_controls: Element<'app, Message>,
     window_size: (u32, u32),
     window_focused: bool,
     fullscreen_button: &'app mut button::State,
     window_mode: Mode,
     quit_button: &'app mut button::State,
+    replay_controls: Option<Element<'app, Message>>,
+    command_controls: Option<Element<'app, Message>>,
+    pyro_controls: Element<'app, Message>,
 ) -> Element<'app, Message> {
     Container::new(
         Column::new()
@@ -157,6 +226,16 @@ fn control_cluster<'app>(
                 time_base.into(),
                 Message::ChangeTimeBase,
             ))
+            .push(mission_controls)
+            .push(
+                replay_controls
+                    .unwrap_or_else(|| Space::new(Length::Shrink, Length::Shrink).into()),
+            )
+            .push(
+                command_controls
+                    .unwrap_or_else(|| Space::new(Length::Shrink, Length::Shrink).into()),
+            )
+            .push(pyro_controls)
             .push(
                 Text::new(format!("Window Size: {:?}", window_size))
                     .horizontal_alignment(HorizontalAlignment::Center),